@@ -0,0 +1,144 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use regex::Regex;
+use syn::{Data, DeriveInput, Error, Fields, LitStr, parse_macro_input};
+
+// How a field is read from the regex captures
+enum FieldKind {
+    // The next capture group, parsed with FromStr
+    Capture,
+    // The next capture group, split on a separator and every element parsed with FromStr
+    Split(LitStr),
+    // Not in the regex, set to its default value
+    Skip,
+}
+
+// Implements aoc_2022::parser::AocParse for a struct with named fields, with the helpers of
+// aoc_2022::parser::__private
+// The regex must match the whole text, and its capture groups are read into the fields in order:
+//
+//     #[derive(AocParse)]
+//     #[aoc(regex = r"move (\d+) from (\d+) to (\d+)")]
+//     struct Instruction { number: usize, from: usize, to: usize }
+//
+// Fields accept #[aoc(split = ", ")] to read a list into a Vec, and #[aoc(skip)] for fields
+// that are not in the input
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let pattern = struct_pattern(&input)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "AocParse can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "AocParse needs a struct with named fields",
+        ));
+    };
+
+    let regex = Regex::new(&pattern.value())
+        .map_err(|e| Error::new(pattern.span(), format!("Invalid regex: {e}")))?;
+
+    let mut group = 0;
+    let mut initializers = vec![];
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named fields have an ident");
+        let field_name = ident.to_string();
+        let value = match field_kind(field)? {
+            FieldKind::Skip => quote!(::std::default::Default::default()),
+            FieldKind::Capture => {
+                group += 1;
+                quote!(::aoc_2022::parser::__private::parse_capture(text, &captures, #group, #field_name)?)
+            }
+            FieldKind::Split(separator) => {
+                group += 1;
+                quote!(::aoc_2022::parser::__private::parse_capture_list(
+                    text, &captures, #group, #field_name, #separator
+                )?)
+            }
+        };
+        initializers.push(quote!(#ident: #value));
+    }
+
+    let groups = regex.captures_len() - 1;
+    if groups != group {
+        return Err(Error::new(
+            pattern.span(),
+            format!("The regex has {groups} capture groups but {group} fields read from it"),
+        ));
+    }
+
+    let type_name = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::aoc_2022::parser::AocParse for #name #ty_generics #where_clause {
+            fn aoc_parse(text: &str) -> ::aoc_2022::error::Result<Self> {
+                static PATTERN: ::std::sync::OnceLock<::aoc_2022::parser::__private::Regex> = ::std::sync::OnceLock::new();
+                let pattern = match PATTERN.get() {
+                    Some(pattern) => pattern,
+                    None => {
+                        let pattern = ::aoc_2022::parser::__private::compile_pattern(#pattern)?;
+                        PATTERN.get_or_init(|| pattern)
+                    }
+                };
+                let captures = ::aoc_2022::parser::__private::match_pattern(pattern, text, #type_name)?;
+                Ok(#name {
+                    #(#initializers,)*
+                })
+            }
+        }
+    })
+}
+
+// Reads #[aoc(regex = "...")] on the struct
+fn struct_pattern(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut pattern = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("regex") {
+                pattern = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("Expected `regex = \"...\"`"))
+            }
+        })?;
+    }
+    pattern.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "AocParse needs a regex, add #[aoc(regex = \"...\")] to the struct",
+        )
+    })
+}
+
+// Reads #[aoc(skip)] or #[aoc(split = "...")] on a field
+fn field_kind(field: &syn::Field) -> syn::Result<FieldKind> {
+    let mut kind = FieldKind::Capture;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                kind = FieldKind::Skip;
+                Ok(())
+            } else if meta.path.is_ident("split") {
+                kind = FieldKind::Split(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("Expected `skip` or `split = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(kind)
+}
@@ -0,0 +1,36 @@
+// Benchmarks parsing and both parts of every registered day, on its real input
+// Run with `cargo bench`, or `cargo bench -- day12` for a single day
+
+use std::hint::black_box;
+
+use aoc_2022::exercises;
+use aoc_2022::solution::Params;
+use criterion::{Criterion, criterion_group, criterion_main};
+
+fn days(c: &mut Criterion) {
+    let params = Params::new();
+    for solution in exercises::solutions() {
+        // Days without their input are skipped, fetch them first
+        let parsed = match solution.parse(&solution.input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Skipping day {}: {e}", solution.day);
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(format!("day{}", solution.day));
+        group.bench_function("parse", |b| {
+            b.iter(|| solution.parse(black_box(&solution.input)))
+        });
+        for part in [1, 2] {
+            group.bench_function(format!("part{part}"), |b| {
+                b.iter(|| parsed.solve(black_box(part), &params))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    example_tests(&root, &out_dir);
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        embedded_inputs(&root, &out_dir);
+    }
+}
+
+fn list_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Unable to list {}: {e}", dir.display()))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+fn write(path: &Path, content: &str) {
+    fs::write(path, content).unwrap_or_else(|e| panic!("Unable to write {}: {e}", path.display()));
+}
+
+// Writes a test per entry of the src/examples/day<N>.toml manifests, read by src/examples.rs
+fn example_tests(root: &Path, out_dir: &Path) {
+    println!("cargo::rerun-if-changed=src/examples");
+    let mut tests = String::new();
+    for path in list_files(&root.join("src/examples")) {
+        let Some(day) = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("day")?.strip_suffix(".toml"))
+        else {
+            continue;
+        };
+        let text = fs::read_to_string(&path).unwrap();
+        let manifest: toml::Table = toml::from_str(&text)
+            .unwrap_or_else(|e| panic!("Invalid manifest {}: {e}", path.display()));
+        let count = manifest
+            .get("example")
+            .and_then(toml::Value::as_array)
+            .map_or(0, Vec::len);
+        for index in 0..count {
+            tests.push_str(&format!(
+                "#[test]\nfn day{day}_example_{}() {{\n    check({day}, {index});\n}}\n\n",
+                index + 1
+            ));
+        }
+    }
+    write(&out_dir.join("examples.rs"), &tests);
+}
+
+// Writes the table of puzzle inputs read by src/input.rs with the `embed-inputs` feature
+fn embedded_inputs(root: &Path, out_dir: &Path) {
+    println!("cargo::rerun-if-changed=src/files");
+    let mut table = String::from("pub static INPUTS: &[(&str, &str)] = &[\n");
+    for path in list_files(&root.join("src/files")) {
+        let name = path.file_name().unwrap().to_string_lossy();
+        table.push_str(&format!(
+            "    ({name:?}, include_str!({:?})),\n",
+            path.display()
+        ));
+    }
+    table.push_str("];\n");
+    write(&out_dir.join("inputs.rs"), &table);
+}
@@ -0,0 +1,131 @@
+use std::fmt::{Display, Formatter, Result};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AocError;
+use crate::grid::Grid;
+
+/// The answer of a puzzle part, whatever the day
+/// Grids are pixel screens such as day 10's, read by a human
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    Grid(Vec<Vec<bool>>),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            // Lit pixels are drawn as '#' and dark ones as '.', one line per row
+            Answer::Grid(rows) => {
+                let lines: Vec<String> = rows
+                    .iter()
+                    .map(|row| row.iter().map(|lit| if *lit { '#' } else { '.' }).collect())
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Integer(n.into())
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Integer(n.into())
+    }
+}
+
+// Answers are stored as i64, larger counts are reported instead of wrapping around
+impl TryFrom<u64> for Answer {
+    type Error = AocError;
+
+    fn try_from(n: u64) -> std::result::Result<Self, AocError> {
+        n.try_into()
+            .map(Answer::Integer)
+            .map_err(|_| AocError::solve(format!("Answer {n} does not fit in an i64")))
+    }
+}
+
+impl TryFrom<usize> for Answer {
+    type Error = AocError;
+
+    fn try_from(n: usize) -> std::result::Result<Self, AocError> {
+        n.try_into()
+            .map(Answer::Integer)
+            .map_err(|_| AocError::solve(format!("Answer {n} does not fit in an i64")))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<Vec<Vec<bool>>> for Answer {
+    fn from(rows: Vec<Vec<bool>>) -> Self {
+        Answer::Grid(rows)
+    }
+}
+
+impl From<Grid<bool>> for Answer {
+    fn from(grid: Grid<bool>) -> Self {
+        Answer::Grid(grid.to_rows())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42).to_string(), "42");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        assert_eq!(
+            Answer::from(vec![vec![true, false], vec![false, true]]).to_string(),
+            "#.\n.#"
+        );
+    }
+
+    #[test]
+    fn test_try_from() {
+        assert_eq!(Answer::try_from(42usize), Ok(Answer::Integer(42)));
+        assert_eq!(
+            Answer::try_from(u64::MAX),
+            Err(AocError::solve(
+                "Answer 18446744073709551615 does not fit in an i64"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let answers = vec![
+            Answer::try_from(32059801242u64).unwrap(),
+            Answer::from("CMZ"),
+            Answer::from(vec![vec![true, false]]),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"type":"integer","value":32059801242},{"type":"text","value":"CMZ"},{"type":"grid","value":[[true,false]]}]"#
+        );
+        assert_eq!(serde_json::from_str::<Vec<Answer>>(&json).unwrap(), answers);
+    }
+}
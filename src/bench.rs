@@ -0,0 +1,395 @@
+//! Timings of every day kept across commits, to notice when a change makes solving slower
+//! Shared code such as parser::lines affects every day, `aoc bench --compare` shows by how much
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::history;
+use crate::solution::{self, Params, Solution, Timings};
+use crate::table::{self, Align};
+
+/// In the crate, so that it can be committed with the code it measures
+pub const HISTORY: &str = "benches/history.json";
+
+/// Phases faster than this vary too much between runs to be compared
+pub const NOISE_FLOOR: Duration = Duration::from_micros(50);
+
+/// The fastest of several runs of each phase of a day, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub parse_ns: u64,
+    pub part1_ns: u64,
+    pub part2_ns: u64,
+}
+
+impl DayBench {
+    pub fn phases(&self) -> [(&'static str, Duration); 3] {
+        [
+            ("parse", Duration::from_nanos(self.parse_ns)),
+            ("part1", Duration::from_nanos(self.part1_ns)),
+            ("part2", Duration::from_nanos(self.part2_ns)),
+        ]
+    }
+
+    pub fn timings(&self) -> Timings {
+        let [(_, parse), (_, part1), (_, part2)] = self.phases();
+        Timings {
+            parse,
+            parts: vec![part1, part2],
+        }
+    }
+}
+
+/// The benchmark of a commit, uncommitted changes are marked by a -dirty suffix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub commit: String,
+    pub date: String,
+    pub days: BTreeMap<u8, DayBench>,
+}
+
+// The fastest of `runs` calls of f
+fn fastest<T>(runs: usize, mut f: impl FnMut() -> Result<T>) -> Result<Duration> {
+    let mut best = Duration::MAX;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        f()?;
+        best = best.min(start.elapsed());
+    }
+    Ok(best)
+}
+
+/// Runs every phase of a day on its real input
+pub fn measure(solution: &Solution, runs: usize) -> Result<DayBench> {
+    let parse = fastest(runs, || solution.parse(&solution.input))?;
+    let parsed = solution.parse(&solution.input)?;
+    let params = Params::new();
+    let part1 = fastest(runs, || parsed.solve(1, &params))?;
+    let part2 = fastest(runs, || parsed.solve(2, &params))?;
+    let nanos = |d: Duration| d.as_nanos() as u64;
+    Ok(DayBench {
+        parse_ns: nanos(parse),
+        part1_ns: nanos(part1),
+        part2_ns: nanos(part2),
+    })
+}
+
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Short hash of the checked out commit, "unknown" outside of a git repository
+pub fn current_commit() -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let Some(hash) = git(root, &["rev-parse", "--short", "HEAD"]) else {
+        return "unknown".to_string();
+    };
+    match git(root, &["status", "--porcelain", "--untracked-files=no"]) {
+        Some(changes) if !changes.is_empty() => format!("{hash}-dirty"),
+        _ => hash,
+    }
+}
+
+/// UTC date and time such as 2022-12-01T05:00:00Z
+pub fn format_date(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, secs) = (secs / 86400, secs % 86400);
+    // Civil date of a day count, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Every recorded benchmark, oldest first, stored as JSON
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    records: Vec<Record>,
+}
+
+impl History {
+    pub fn path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(HISTORY)
+    }
+
+    pub fn load(path: &Path) -> Result<History> {
+        Ok(History {
+            path: path.to_path_buf(),
+            records: history::load(path, "benchmark history")?,
+        })
+    }
+
+    /// The latest benchmark of another commit, to compare a new one with
+    pub fn baseline(&self, commit: &str) -> Option<&Record> {
+        self.records.iter().rev().find(|r| r.commit != commit)
+    }
+
+    /// Benchmarking a commit again updates the days that were measured
+    pub fn record(&mut self, record: Record) -> Result<()> {
+        match self.records.iter_mut().find(|r| r.commit == record.commit) {
+            Some(existing) => {
+                existing.date = record.date;
+                existing.days.extend(record.days);
+            }
+            None => self.records.push(record),
+        }
+        history::save(&self.path, &self.records)
+    }
+}
+
+/// A phase measured in both benchmarks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub day: u8,
+    pub phase: &'static str,
+    pub before: Duration,
+    pub after: Duration,
+    pub regressed: bool,
+}
+
+impl Comparison {
+    /// Relative change in percent, positive when slower
+    pub fn change(&self) -> f64 {
+        (self.after.as_secs_f64() / self.before.as_secs_f64() - 1.0) * 100.0
+    }
+}
+
+/// A phase regressed when it got slower by more than threshold percent
+/// Phases under NOISE_FLOOR in both benchmarks are never flagged
+pub fn compare(before: &Record, after: &Record, threshold: f64) -> Vec<Comparison> {
+    let mut comparisons = vec![];
+    for (day, bench) in &after.days {
+        let Some(previous) = before.days.get(day) else {
+            continue;
+        };
+        for ((phase, before), (_, after)) in previous.phases().into_iter().zip(bench.phases()) {
+            let mut comparison = Comparison {
+                day: *day,
+                phase,
+                before,
+                after,
+                regressed: false,
+            };
+            comparison.regressed = after.max(before) >= NOISE_FLOOR
+                && !before.is_zero()
+                && comparison.change() > threshold;
+            comparisons.push(comparison);
+        }
+    }
+    comparisons
+}
+
+pub fn render_comparison(before: &Record, comparisons: &[Comparison], threshold: f64) -> String {
+    let mut rows = vec![];
+    for c in comparisons {
+        rows.push(vec![
+            c.day.to_string(),
+            c.phase.to_string(),
+            format!("{:.1?}", c.before),
+            format!("{:.1?}", c.after),
+            format!("{:+.1}%", c.change()),
+            if c.regressed { "REGRESSED" } else { "" }.to_string(),
+        ]);
+    }
+    let columns = [
+        ("Day", Align::Right),
+        ("Phase", Align::Left),
+        ("Before", Align::Right),
+        ("After", Align::Right),
+        ("Change", Align::Right),
+        ("", Align::Left),
+    ];
+
+    let mut table = vec![
+        format!("Compared with {} ({})", before.commit, before.date),
+        table::render(&columns, &rows),
+    ];
+    let regressed = comparisons.iter().filter(|c| c.regressed).count();
+    table.push(format!(
+        "{regressed} of {} phases regressed by more than {threshold}%",
+        comparisons.len()
+    ));
+    table.join("\n")
+}
+
+/// How `aoc-2022 bench` times the selected days
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    /// Each phase is run this many times, the fastest run is kept
+    pub runs: usize,
+    /// Compare with the previous commit of the history
+    pub compare: bool,
+    /// Slowdown in percent above which a phase is reported as regressed
+    pub threshold: f64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> BenchOptions {
+        BenchOptions {
+            runs: 10,
+            compare: false,
+            threshold: 10.0,
+        }
+    }
+}
+
+/// Times the days, prints their timings and records them in the history of History::path
+/// Returns false if a day failed or, when comparing, if a phase regressed
+pub fn bench(solutions: &[Solution], options: &BenchOptions) -> bool {
+    let mut failed = false;
+    let mut record = Record {
+        commit: current_commit(),
+        date: format_date(SystemTime::now()),
+        days: Default::default(),
+    };
+    for solution in solutions {
+        match measure(solution, options.runs) {
+            Ok(day) => {
+                record.days.insert(solution.day, day);
+            }
+            Err(e) => {
+                failed = true;
+                eprintln!("Day {}: {}", solution.day, e.diagnostic());
+            }
+        }
+    }
+    let timings: Vec<(u8, Timings)> = record
+        .days
+        .iter()
+        .map(|(day, bench)| (*day, bench.timings()))
+        .collect();
+    println!("{}", solution::render_timings(&timings, &[1, 2]));
+
+    let path = History::path();
+    let recorded = History::load(&path).and_then(|mut history| {
+        if options.compare {
+            match history.baseline(&record.commit) {
+                Some(baseline) => {
+                    let comparisons = compare(baseline, &record, options.threshold);
+                    let table = render_comparison(baseline, &comparisons, options.threshold);
+                    println!("\n{table}");
+                    failed |= comparisons.iter().any(|c| c.regressed);
+                }
+                None => println!("\nNo benchmark of another commit to compare with"),
+            }
+        }
+        history.record(record.clone())
+    });
+    match recorded {
+        Ok(()) => println!("Recorded as {} in {}", record.commit, path.display()),
+        Err(e) => {
+            failed = true;
+            eprintln!("{}", e.diagnostic());
+        }
+    }
+    !failed
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn bench(parse_us: u64, part1_us: u64, part2_us: u64) -> DayBench {
+        DayBench {
+            parse_ns: parse_us * 1000,
+            part1_ns: part1_us * 1000,
+            part2_ns: part2_us * 1000,
+        }
+    }
+
+    fn record(commit: &str, days: &[(u8, DayBench)]) -> Record {
+        Record {
+            commit: commit.to_string(),
+            date: "2022-12-25T06:00:00Z".to_string(),
+            days: days.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        let date = UNIX_EPOCH + Duration::from_secs(1_669_870_800);
+        assert_eq!(format_date(date), "2022-12-01T05:00:00Z");
+        let leap = UNIX_EPOCH + Duration::from_secs(1_709_164_799);
+        assert_eq!(format_date(leap), "2024-02-28T23:59:59Z");
+    }
+
+    #[test]
+    fn test_compare() {
+        let before = record(
+            "aaaaaaa",
+            &[(8, bench(120, 600, 800)), (12, bench(50, 480, 100_000))],
+        );
+        let after = record(
+            "bbbbbbb",
+            &[
+                (8, bench(180, 610, 790)),
+                (12, bench(10, 470, 130_000)),
+                (13, bench(1000, 10, 1300)),
+            ],
+        );
+        let comparisons = compare(&before, &after, 10.0);
+        let regressed: Vec<_> = comparisons
+            .iter()
+            .filter(|c| c.regressed)
+            .map(|c| (c.day, c.phase))
+            .collect();
+        assert_eq!(regressed, vec![(8, "parse"), (12, "part2")]);
+        assert_snapshot!(render_comparison(&before, &comparisons, 10.0));
+
+        // Changes under the noise floor are not flagged
+        let before = record("a", &[(1, bench(5, 1, 1))]);
+        let after = record("b", &[(1, bench(20, 3, 1))]);
+        assert!(compare(&before, &after, 10.0).iter().all(|c| !c.regressed));
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = TempDir::new("bench");
+        let path = dir.join("history.json");
+        let mut history = History::load(&path).unwrap();
+        history
+            .record(record("aaa", &[(1, bench(1, 1, 1))]))
+            .unwrap();
+        history
+            .record(record("bbb", &[(1, bench(2, 2, 2))]))
+            .unwrap();
+        history
+            .record(record("bbb", &[(2, bench(3, 3, 3))]))
+            .unwrap();
+
+        let history = History::load(&path).unwrap();
+        assert_eq!(history.records.len(), 2);
+        assert_eq!(history.records[1].days.len(), 2);
+        assert_eq!(history.baseline("bbb").unwrap().commit, "aaa");
+        assert_eq!(history.baseline("ccc").unwrap().commit, "bbb");
+    }
+}
@@ -0,0 +1,137 @@
+//! Limits on the time and memory a day can take, so that a runaway solver does not hang a run
+//! Each day runs in its own process (see runner.rs), killed once it runs out of time
+//! Memory is counted by CountingAllocator, which the binary installs as its global allocator:
+//!
+//! ```
+//! use aoc_2022::budget::CountingAllocator;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//! # fn main() {}
+//! ```
+//!
+//! A process that would allocate more than its limit exits at once with OVER_BUDGET_STATUS
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// The budget of each day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Wall-clock time to parse the input and solve the parts
+    pub timeout: Option<Duration>,
+    /// Peak of the memory allocated by the solver, in bytes
+    pub memory: Option<usize>,
+}
+
+impl Default for Limits {
+    // Far above what any day takes, only runaways reach them
+    fn default() -> Limits {
+        Limits {
+            timeout: Some(Duration::from_secs(60)),
+            memory: Some(1 << 30),
+        }
+    }
+}
+
+/// Exit status of a process stopped by its memory limit
+pub const OVER_BUDGET_STATUS: i32 = 3;
+
+// Bytes allocated by the whole process, counted by CountingAllocator
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+/// Makes the process exit with OVER_BUDGET_STATUS rather than hold more than limit bytes at once
+pub fn limit_memory(limit: Option<usize>) {
+    LIMIT.store(limit.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// Most memory the process held at once, 0 without the counting allocator
+pub fn peak_memory() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+fn reserve(size: usize) {
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    if current > LIMIT.load(Ordering::Relaxed) && !STOPPING.swap(true, Ordering::Relaxed) {
+        // Exiting may allocate, which is let through by STOPPING
+        process::exit(OVER_BUDGET_STATUS);
+    }
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+fn release(size: usize) {
+    CURRENT.fetch_sub(size, Ordering::Relaxed);
+}
+
+/// Counts the allocations of the process against its limit, and allocates with System
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        reserve(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        reserve(layout.size());
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        release(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let old_size = layout.size();
+        if new_size > old_size {
+            reserve(new_size - old_size);
+        }
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if new_size < old_size {
+            release(old_size - new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Such as 512 B, 1.5 KiB or 1.0 GiB
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(1 << 30), "1.0 GiB");
+    }
+
+    #[test]
+    fn test_peak_memory() {
+        let large = black_box(vec![0u8; 64 << 20]);
+        assert!(peak_memory() >= 64 << 20);
+        drop(large);
+    }
+}
@@ -0,0 +1,491 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use aoc_2022::bench::BenchOptions;
+use aoc_2022::runner::RunOptions;
+
+pub const USAGE: &str = "Usage: aoc-2022 [run] [all | <day> | <first>-<last>] [<part>]
+                       [--time] [--jobs <n>] [--timeout <seconds>] [--memory <MiB>]
+                       [--format text|json|csv]
+       aoc-2022 fetch <day>
+       aoc-2022 submit <day> <part>
+       aoc-2022 verify [all | <day> | <first>-<last>]
+       aoc-2022 new <day>
+       aoc-2022 examples <day>
+       aoc-2022 watch <day>
+       aoc-2022 bench [all | <day> | <first>-<last>] [--runs <n>] [--compare] [--threshold <percent>]
+
+Examples:
+  aoc-2022          runs every registered day
+  aoc-2022 7        runs both parts of day 7
+  aoc-2022 7 2      runs part 2 of day 7
+  aoc-2022 3-9      runs days 3 to 9 included
+  aoc-2022 run --time
+                    runs every day and prints how long each phase took
+  aoc-2022 all --jobs 4
+                    runs every day on 4 threads, by default one per core
+  aoc-2022 9 --timeout 5 --memory 100
+                    kills day 9 if it takes more than 5s or 100 MiB,
+                    by default 60s and 1024 MiB
+  aoc-2022 run --format json
+                    prints a JSON record per day and part, with its answer,
+                    status and timings
+  aoc-2022 fetch 7  downloads the input of day 7 into the cache
+  aoc-2022 submit 7 2
+                    solves part 2 of day 7 and sends the answer to the website
+  aoc-2022 verify   checks every answer against src/files/answers.toml
+  aoc-2022 new 14   writes and registers a module for day 14, with empty input
+                    and example files
+  aoc-2022 examples 9
+                    checks the examples of day 9 against src/examples/day9.toml
+  aoc-2022 watch 9  rebuilds, tests and runs day 9 whenever its module, input
+                    or examples change
+  aoc-2022 bench --compare
+                    times every day, records it in benches/history.json and
+                    fails if a phase got slower than in the previous commit";
+
+// What the user asked for
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Selection, RunOptions),
+    Fetch {
+        day: u8,
+    },
+    Submit {
+        day: u8,
+        part: u8,
+    },
+    Verify {
+        days: RangeInclusive<u8>,
+    },
+    New {
+        day: u8,
+    },
+    Examples {
+        day: u8,
+    },
+    Watch {
+        day: u8,
+    },
+    Bench {
+        days: RangeInclusive<u8>,
+        options: BenchOptions,
+    },
+}
+
+// Which days and parts the user asked to run
+#[derive(Debug, PartialEq)]
+pub struct Selection {
+    pub days: RangeInclusive<u8>,
+    pub part: Option<u8>,
+}
+
+impl Selection {
+    pub fn all() -> Selection {
+        Selection {
+            days: 1..=25,
+            part: None,
+        }
+    }
+
+    pub fn contains_day(&self, day: u8) -> bool {
+        self.days.contains(&day)
+    }
+
+    pub fn parts(&self) -> Vec<u8> {
+        match self.part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        }
+    }
+}
+
+fn parse_day(raw: &str) -> Result<u8, String> {
+    match raw.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!(
+            "Invalid day {raw}, expected a number between 1 and 25"
+        )),
+    }
+}
+
+fn parse_days(raw: &str) -> Result<RangeInclusive<u8>, String> {
+    if raw == "all" {
+        return Ok(1..=25);
+    }
+
+    let Some((first, last)) = raw.split_once('-') else {
+        let day = parse_day(raw)?;
+        return Ok(day..=day);
+    };
+    let (first, last) = (parse_day(first)?, parse_day(last)?);
+    if first > last {
+        return Err(format!("Invalid range {raw}, {first} is after {last}"));
+    }
+    Ok(first..=last)
+}
+
+fn parse_part(raw: &str) -> Result<u8, String> {
+    match raw {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("Invalid part {raw}, expected 1 or 2")),
+    }
+}
+
+// Parses the arguments given after the binary name
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args {
+        [command, rest @ ..] if command == "fetch" => match rest {
+            [day] => Ok(Command::Fetch {
+                day: parse_day(day)?,
+            }),
+            _ => Err("fetch expects a single day".to_string()),
+        },
+        [command, rest @ ..] if command == "submit" => match rest {
+            [day, part] => Ok(Command::Submit {
+                day: parse_day(day)?,
+                part: parse_part(part)?,
+            }),
+            _ => Err("submit expects a day and a part".to_string()),
+        },
+        [command, rest @ ..] if command == "verify" => match rest {
+            [] => Ok(Command::Verify { days: 1..=25 }),
+            [days] => Ok(Command::Verify {
+                days: parse_days(days)?,
+            }),
+            _ => Err("verify expects at most a range of days".to_string()),
+        },
+        [command, rest @ ..] if command == "new" => match rest {
+            [day] => Ok(Command::New {
+                day: parse_day(day)?,
+            }),
+            _ => Err("new expects a single day".to_string()),
+        },
+        [command, rest @ ..] if command == "examples" => match rest {
+            [day] => Ok(Command::Examples {
+                day: parse_day(day)?,
+            }),
+            _ => Err("examples expects a single day".to_string()),
+        },
+        [command, rest @ ..] if command == "watch" => match rest {
+            [day] => Ok(Command::Watch {
+                day: parse_day(day)?,
+            }),
+            _ => Err("watch expects a single day".to_string()),
+        },
+        [command, rest @ ..] if command == "bench" => parse_bench(rest),
+        [command, rest @ ..] if command == "run" => parse_run(rest),
+        _ => parse_run(args),
+    }
+}
+
+// Options start with --, the other arguments select the days and parts
+fn parse_run(args: &[String]) -> Result<Command, String> {
+    let mut options = RunOptions::default();
+    let mut selection = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--time" => options.time = true,
+            "--jobs" => {
+                let raw = args.next().ok_or("--jobs expects a value")?;
+                options.jobs = match raw.parse() {
+                    Ok(jobs) if jobs > 0 => Some(jobs),
+                    _ => return Err(format!("Invalid number of jobs {raw}")),
+                };
+            }
+            "--timeout" => {
+                let raw = args.next().ok_or("--timeout expects a value")?;
+                options.limits.timeout = match raw.trim_end_matches('s').parse::<f64>() {
+                    Ok(secs) if secs > 0.0 => Some(Duration::from_secs_f64(secs)),
+                    _ => return Err(format!("Invalid timeout {raw}, expected seconds")),
+                };
+            }
+            "--format" => {
+                let raw = args.next().ok_or("--format expects a value")?;
+                options.format = raw.parse()?;
+            }
+            "--memory" => {
+                let raw = args.next().ok_or("--memory expects a value")?;
+                options.limits.memory = match raw.parse::<usize>() {
+                    Ok(mib) if mib > 0 => Some(mib << 20),
+                    _ => return Err(format!("Invalid memory budget {raw}, expected MiB")),
+                };
+            }
+            option if option.starts_with("--") => return Err(format!("Unknown option {option}")),
+            _ => selection.push(arg.clone()),
+        }
+    }
+    Ok(Command::Run(parse_selection(&selection)?, options))
+}
+
+fn parse_bench(args: &[String]) -> Result<Command, String> {
+    let mut options = BenchOptions::default();
+    let mut days = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{name} expects a value"))
+                .cloned()
+        };
+        match arg.as_str() {
+            "--compare" => options.compare = true,
+            "--runs" => {
+                let raw = value("--runs")?;
+                options.runs = match raw.parse() {
+                    Ok(runs) if runs > 0 => runs,
+                    _ => return Err(format!("Invalid number of runs {raw}")),
+                };
+            }
+            "--threshold" => {
+                let raw = value("--threshold")?;
+                options.threshold = match raw.trim_end_matches('%').parse::<f64>() {
+                    Ok(threshold) if threshold >= 0.0 => threshold,
+                    _ => return Err(format!("Invalid threshold {raw}, expected a percentage")),
+                };
+            }
+            option if option.starts_with("--") => return Err(format!("Unknown option {option}")),
+            _ if days.is_some() => {
+                return Err("bench expects at most a range of days".to_string());
+            }
+            _ => days = Some(parse_days(arg)?),
+        }
+    }
+    Ok(Command::Bench {
+        days: days.unwrap_or(1..=25),
+        options,
+    })
+}
+
+fn parse_selection(args: &[String]) -> Result<Selection, String> {
+    match args {
+        [] => Ok(Selection::all()),
+        [days] => Ok(Selection {
+            days: parse_days(days)?,
+            part: None,
+        }),
+        [days, part] => Ok(Selection {
+            days: parse_days(days)?,
+            part: Some(parse_part(part)?),
+        }),
+        _ => Err("Too many arguments".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_2022::budget::Limits;
+    use aoc_2022::output::Format;
+
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_all() {
+        let all = || Ok(Command::Run(Selection::all(), RunOptions::default()));
+        assert_eq!(parse_args(&args(&[])), all());
+        assert_eq!(parse_args(&args(&["all"])), all());
+        assert_eq!(parse_args(&args(&["run"])), all());
+    }
+
+    #[test]
+    fn test_parse_args_day_and_part() {
+        let selection = parse_selection(&args(&["7", "2"])).unwrap();
+        assert_eq!(selection.days, 7..=7);
+        assert_eq!(selection.parts(), vec![2]);
+    }
+
+    #[test]
+    fn test_parse_args_range() {
+        let selection = parse_selection(&args(&["3-9"])).unwrap();
+        assert!(!selection.contains_day(2));
+        assert!(selection.contains_day(3));
+        assert!(selection.contains_day(9));
+        assert!(!selection.contains_day(10));
+        assert_eq!(selection.parts(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(parse_args(&args(&["0"])).is_err());
+        assert!(parse_args(&args(&["26"])).is_err());
+        assert!(parse_args(&args(&["9-3"])).is_err());
+        assert!(parse_args(&args(&["7", "3"])).is_err());
+        assert!(parse_args(&args(&["7", "1", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_time() {
+        let expected = Command::Run(
+            Selection {
+                days: 7..=7,
+                part: Some(2),
+            },
+            RunOptions {
+                time: true,
+                ..RunOptions::default()
+            },
+        );
+        assert_eq!(
+            parse_args(&args(&["run", "7", "2", "--time"])),
+            Ok(expected)
+        );
+        assert_eq!(
+            parse_args(&args(&["--time"])),
+            Ok(Command::Run(
+                Selection::all(),
+                RunOptions {
+                    time: true,
+                    ..RunOptions::default()
+                }
+            ))
+        );
+        assert!(parse_args(&args(&["--fast"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_jobs() {
+        assert_eq!(
+            parse_args(&args(&["all", "--jobs", "4"])),
+            Ok(Command::Run(
+                Selection::all(),
+                RunOptions {
+                    jobs: Some(4),
+                    ..RunOptions::default()
+                }
+            ))
+        );
+        assert!(parse_args(&args(&["--jobs"])).is_err());
+        assert!(parse_args(&args(&["--jobs", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_limits() {
+        let Ok(Command::Run(_, options)) =
+            parse_args(&args(&["9", "--timeout", "2.5", "--memory", "100"]))
+        else {
+            panic!("Expected a run");
+        };
+        assert_eq!(
+            options.limits,
+            Limits {
+                timeout: Some(Duration::from_millis(2500)),
+                memory: Some(100 << 20),
+            }
+        );
+        assert_eq!(RunOptions::default().limits, Limits::default());
+        assert!(parse_args(&args(&["--timeout", "0"])).is_err());
+        assert!(parse_args(&args(&["--memory", "lots"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_format() {
+        let format = |raw: &[&str]| match parse_args(&args(raw)) {
+            Ok(Command::Run(_, options)) => Ok(options.format),
+            Ok(command) => panic!("Expected a run, got {command:?}"),
+            Err(e) => Err(e),
+        };
+        assert_eq!(format(&["run"]), Ok(Format::Text));
+        assert_eq!(format(&["run", "--format", "json"]), Ok(Format::Json));
+        assert_eq!(format(&["3-9", "--format", "csv"]), Ok(Format::Csv));
+        assert!(format(&["--format", "xml"]).is_err());
+        assert!(format(&["--format"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_fetch() {
+        assert_eq!(
+            parse_args(&args(&["fetch", "7"])),
+            Ok(Command::Fetch { day: 7 })
+        );
+        assert!(parse_args(&args(&["fetch"])).is_err());
+        assert!(parse_args(&args(&["fetch", "26"])).is_err());
+        assert!(parse_args(&args(&["fetch", "7", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_submit() {
+        assert_eq!(
+            parse_args(&args(&["submit", "7", "2"])),
+            Ok(Command::Submit { day: 7, part: 2 })
+        );
+        assert!(parse_args(&args(&["submit", "7"])).is_err());
+        assert!(parse_args(&args(&["submit", "7", "3"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_verify() {
+        assert_eq!(
+            parse_args(&args(&["verify"])),
+            Ok(Command::Verify { days: 1..=25 })
+        );
+        assert_eq!(
+            parse_args(&args(&["verify", "3-9"])),
+            Ok(Command::Verify { days: 3..=9 })
+        );
+        assert!(parse_args(&args(&["verify", "3", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_new() {
+        assert_eq!(
+            parse_args(&args(&["new", "14"])),
+            Ok(Command::New { day: 14 })
+        );
+        assert!(parse_args(&args(&["new"])).is_err());
+        assert!(parse_args(&args(&["new", "30"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_watch() {
+        assert_eq!(
+            parse_args(&args(&["examples", "9"])),
+            Ok(Command::Examples { day: 9 })
+        );
+        assert_eq!(
+            parse_args(&args(&["watch", "9"])),
+            Ok(Command::Watch { day: 9 })
+        );
+        assert!(parse_args(&args(&["watch"])).is_err());
+        assert!(parse_args(&args(&["watch", "9", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_bench() {
+        assert_eq!(
+            parse_args(&args(&["bench"])),
+            Ok(Command::Bench {
+                days: 1..=25,
+                options: BenchOptions::default(),
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&[
+                "bench",
+                "--compare",
+                "8-12",
+                "--threshold",
+                "25%",
+                "--runs",
+                "3"
+            ])),
+            Ok(Command::Bench {
+                days: 8..=12,
+                options: BenchOptions {
+                    runs: 3,
+                    compare: true,
+                    threshold: 25.0,
+                },
+            })
+        );
+        assert!(parse_args(&args(&["bench", "--threshold"])).is_err());
+        assert!(parse_args(&args(&["bench", "--threshold", "x"])).is_err());
+        assert!(parse_args(&args(&["bench", "--runs", "0"])).is_err());
+        assert!(parse_args(&args(&["bench", "1", "2"])).is_err());
+    }
+}
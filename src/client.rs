@@ -0,0 +1,431 @@
+//! Talks to the Advent of Code website
+//!
+//! Requests are authenticated with the session cookie of a logged in browser, read from
+//! AOC_SESSION or from the `session` file in the aoc-2022 config directory
+//! Requests are spaced by at least MIN_INTERVAL, and a server asking to slow down is obeyed until
+//! the time it gave, even across runs
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ureq::Agent;
+
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::input;
+use crate::submit::{Outcome, parse_outcome};
+
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const YEAR: u16 = 2022;
+
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+// Longer waits are reported instead of blocking the command
+const MAX_WAIT: Duration = Duration::from_secs(60);
+const USER_AGENT: &str = "aoc-2022 solver";
+
+/// Where and how to reach the website
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub cache_dir: PathBuf,
+    pub min_interval: Duration,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Config> {
+        let cache_dir = input::cache_dir().ok_or_else(|| AocError::Config {
+            message: format!("No cache directory found, set {}", input::CACHE_DIR_VAR),
+        })?;
+        Ok(Config {
+            base_url: env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            session: read_session(),
+            cache_dir,
+            min_interval: MIN_INTERVAL,
+        })
+    }
+}
+
+fn read_session() -> Option<String> {
+    let session = env::var(SESSION_VAR).ok().or_else(|| {
+        let file = dirs::config_dir()?.join("aoc-2022").join("session");
+        fs::read_to_string(file).ok()
+    })?;
+    let session = session.trim();
+    (!session.is_empty()).then(|| session.to_string())
+}
+
+/// What fetch_input did to provide the input
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    Cached(PathBuf),
+}
+
+pub struct Client {
+    config: Config,
+    agent: Agent,
+}
+
+impl Client {
+    pub fn new(config: Config) -> Client {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .user_agent(USER_AGENT)
+            .build()
+            .into();
+        Client { config, agent }
+    }
+
+    /// Downloads the input of a day into the cache, unless it is already there
+    pub fn fetch_input(&self, day: u8) -> Result<Fetched> {
+        let path = input::cached_input(&self.config.cache_dir, &input::input_name(day));
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let url = format!("{}/{YEAR}/day/{day}/input", self.config.base_url);
+        match self.request(&url, None)? {
+            (200, text) => {
+                write_file(&path, &text)?;
+                Ok(Fetched::Downloaded(path))
+            }
+            (404, _) => Err(remote_error(
+                &url,
+                "Not found, the puzzle may not be unlocked yet",
+            )),
+            (status, _) => Err(remote_error(&url, format!("Unexpected status {status}"))),
+        }
+    }
+
+    /// Sends the answer of a part and reads what the website thought of it
+    pub fn submit_answer(&self, day: u8, part: u8, answer: &Answer) -> Result<Outcome> {
+        let url = format!("{}/{YEAR}/day/{day}/answer", self.config.base_url);
+        let form = [("level", part.to_string()), ("answer", answer.to_string())];
+        let form: Vec<(&str, &str)> = form.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let (status, page) = self.request(&url, Some(&form))?;
+        if status != 200 {
+            return Err(remote_error(&url, format!("Unexpected status {status}")));
+        }
+        let outcome = parse_outcome(&page)
+            .ok_or_else(|| remote_error(&url, "The answer page could not be understood"))?;
+        // The wait only shows in the page, it is kept like a Retry-After header
+        if let Outcome::RateLimited(wait) = outcome {
+            self.record_request(Some(wait))?;
+        }
+        Ok(outcome)
+    }
+
+    fn session(&self) -> Result<&str> {
+        self.config
+            .session
+            .as_deref()
+            .ok_or_else(|| AocError::Config {
+                message: format!(
+                    "No session cookie, set {SESSION_VAR} or write it to the aoc-2022/session \
+                     file of your config directory"
+                ),
+            })
+    }
+
+    // Sends a GET request, or a POST of the form when there is one
+    // Returns the status and body of answers that are not a refused session or a rate limit
+    fn request(&self, url: &str, form: Option<&[(&str, &str)]>) -> Result<(u16, String)> {
+        let session = self.session()?;
+        self.throttle(url)?;
+
+        let cookie = format!("session={session}");
+        let response = match form {
+            None => self.agent.get(url).header("Cookie", &cookie).call(),
+            Some(form) => self
+                .agent
+                .post(url)
+                .header("Cookie", &cookie)
+                .send_form(form.iter().copied()),
+        };
+        let mut response = response.map_err(|e| remote_error(url, e))?;
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+            .map(Duration::from_secs);
+        self.record_request(retry_after)?;
+
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| remote_error(url, e))?;
+        match response.status().as_u16() {
+            400 | 401 => Err(remote_error(url, "The session cookie was refused")),
+            429 => Err(remote_error(
+                url,
+                format!(
+                    "Rate limited, retry in {}s",
+                    retry_after.unwrap_or(self.config.min_interval).as_secs()
+                ),
+            )),
+            status => Ok((status, body)),
+        }
+    }
+
+    // Holds the time before which no request should be sent
+    fn next_request_file(&self) -> PathBuf {
+        self.config.cache_dir.join("next-request")
+    }
+
+    // Waits until a request is allowed, or fails if that takes too long
+    fn throttle(&self, url: &str) -> Result<()> {
+        let Ok(content) = fs::read_to_string(self.next_request_file()) else {
+            return Ok(());
+        };
+        let next = content
+            .trim()
+            .parse()
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap_or(UNIX_EPOCH);
+        let Ok(wait) = next.duration_since(SystemTime::now()) else {
+            return Ok(());
+        };
+        if wait > MAX_WAIT {
+            return Err(remote_error(
+                url,
+                format!("Rate limited, retry in {}s", wait.as_secs()),
+            ));
+        }
+        thread::sleep(wait);
+        Ok(())
+    }
+
+    fn record_request(&self, retry_after: Option<Duration>) -> Result<()> {
+        let delay = retry_after.map_or(self.config.min_interval, |retry| {
+            retry.max(self.config.min_interval)
+        });
+        // Rounded up so that the wait is never shorter than asked
+        let next = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            + delay.as_secs()
+            + u64::from(delay.subsec_nanos() > 0);
+        write_file(&self.next_request_file(), &next.to_string())
+    }
+}
+
+fn remote_error(url: &str, message: impl ToString) -> AocError {
+    AocError::Remote {
+        url: url.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AocError::io(dir, e))?;
+    }
+    fs::write(path, content).map_err(|e| AocError::io(path, e))
+}
+
+/// Downloads the input of a day into the cache unless it is already there, and tells where it is
+/// Returns whether the input is now cached
+pub fn fetch(day: u8) -> bool {
+    let fetched = Config::from_env().and_then(|config| Client::new(config).fetch_input(day));
+    match fetched {
+        Ok(Fetched::Downloaded(path)) => println!("Day {day}: downloaded to {}", path.display()),
+        Ok(Fetched::Cached(path)) => println!("Day {day}: already in {}", path.display()),
+        Err(e) => {
+            eprintln!("Day {day}: error: {e}");
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    use super::*;
+    use crate::submit::Verdict;
+    use crate::testing::TempDir;
+
+    // Status, headers and body sent back by the stub server
+    type StubResponse = (u16, Vec<(&'static str, &'static str)>, &'static str);
+
+    // A local server answering the given responses in order, one per connection, and returning
+    // the requests it received
+    fn stub_server(responses: Vec<StubResponse>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, headers, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                requests.push(request);
+
+                let mut response = format!(
+                    "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    body.len()
+                );
+                for (name, value) in headers {
+                    response.push_str(&format!("{name}: {value}\r\n"));
+                }
+                response.push_str("\r\n");
+                response.push_str(body);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (base_url, handle)
+    }
+
+    fn test_config(cache_dir: &TempDir, base_url: &str) -> Config {
+        Config {
+            base_url: base_url.to_string(),
+            session: Some("abc123".to_string()),
+            cache_dir: cache_dir.path().to_path_buf(),
+            min_interval: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_fetch_input_is_cached() {
+        let (base_url, server) = stub_server(vec![(200, vec![], "1\n2\n")]);
+        let dir = TempDir::new("fetch");
+        let config = test_config(&dir, &base_url);
+        let path = config.cache_dir.join("inputs").join("day3");
+        let client = Client::new(config);
+
+        assert_eq!(client.fetch_input(3), Ok(Fetched::Downloaded(path.clone())));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n");
+        // The server only answers once, a second download would not be answered
+        assert_eq!(client.fetch_input(3), Ok(Fetched::Cached(path)));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2022/day/3/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=abc123"));
+    }
+
+    #[test]
+    fn test_fetch_input_errors() {
+        let (base_url, server) = stub_server(vec![
+            (404, vec![], "Please don't repeatedly request this endpoint"),
+            (429, vec![("Retry-After", "120")], ""),
+        ]);
+        let dir = TempDir::new("fetch-errors");
+        let client = Client::new(test_config(&dir, &base_url));
+
+        let url = format!("{base_url}/2022/day/25/input");
+        assert_eq!(
+            client.fetch_input(25),
+            Err(AocError::Remote {
+                url: url.clone(),
+                message: "Not found, the puzzle may not be unlocked yet".to_string()
+            })
+        );
+        assert_eq!(
+            client.fetch_input(25),
+            Err(AocError::Remote {
+                url: url.clone(),
+                message: "Rate limited, retry in 120s".to_string()
+            })
+        );
+        // Refused locally, without reaching the server
+        assert!(matches!(
+            client.fetch_input(25),
+            Err(AocError::Remote { message, .. }) if message.starts_with("Rate limited")
+        ));
+        assert_eq!(server.join().unwrap().len(), 2);
+        assert!(!client.config.cache_dir.join("inputs").exists());
+    }
+
+    #[test]
+    fn test_submit_answer() {
+        let (base_url, server) = stub_server(vec![
+            (
+                200,
+                vec![],
+                "<article><p>That's not the right answer; your answer is too low.</p></article>",
+            ),
+            (
+                200,
+                vec![],
+                "<article><p>You gave an answer too recently. You have 42s left to wait.</p>",
+            ),
+            (200, vec![], "<html>Maintenance</html>"),
+        ]);
+        let dir = TempDir::new("submit");
+        let client = Client::new(test_config(&dir, &base_url));
+
+        assert_eq!(
+            client.submit_answer(7, 2, &Answer::Integer(1234)),
+            Ok(Outcome::Judged(Verdict::TooLow))
+        );
+        assert_eq!(
+            client.submit_answer(7, 2, &Answer::Text("ABC".to_string())),
+            Ok(Outcome::RateLimited(Duration::from_secs(42)))
+        );
+        assert!(
+            fs::read_to_string(client.next_request_file())
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+                >= SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+                    + 41
+        );
+        // The next request waits for the rate limit, which the test can't afford
+        fs::remove_file(client.next_request_file()).unwrap();
+        assert!(matches!(
+            client.submit_answer(7, 2, &Answer::Integer(1)),
+            Err(AocError::Remote { message, .. }) if message.contains("could not be understood")
+        ));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/7/answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=abc123"));
+        assert!(requests[0].ends_with("level=2&answer=1234"));
+        assert!(requests[1].ends_with("level=2&answer=ABC"));
+    }
+
+    #[test]
+    fn test_fetch_input_without_session() {
+        let dir = TempDir::new("no-session");
+        let mut config = test_config(&dir, "http://127.0.0.1:1");
+        config.session = None;
+        let client = Client::new(config);
+        assert!(matches!(
+            client.fetch_input(1),
+            Err(AocError::Config { .. })
+        ));
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt::{Display, Formatter};
+use std::num::ParseIntError;
+
+use serde::{Deserialize, Serialize};
+
+use crate::input;
+
+/// Where an error happened in an input file
+/// Lines and columns start at 1, like in editors
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub col: Option<usize>,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file.as_deref().unwrap_or("<input>"))?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+            if let Some(col) = self.col {
+                write!(f, ":{col}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AocError {
+    /// A file could not be read or written
+    Io { file: String, message: String },
+    /// A regex used to read the input does not compile
+    InvalidPattern { pattern: String, message: String },
+    /// The input does not have the expected format
+    Parse { location: Location, message: String },
+    /// The input was read but the puzzle can't be solved with it
+    Solve { message: String },
+    /// Several lines of the input could not be parsed, in the order they were read
+    Multiple(Vec<AocError>),
+    /// The Advent of Code website could not be reached or refused the request
+    Remote { url: String, message: String },
+    /// Something needed to talk to the website is missing, such as the session cookie
+    Config { message: String },
+}
+
+pub type Result<T> = std::result::Result<T, AocError>;
+
+impl AocError {
+    pub fn parse(message: impl Into<String>) -> AocError {
+        AocError::Parse {
+            location: Location::default(),
+            message: message.into(),
+        }
+    }
+
+    pub fn solve(message: impl Into<String>) -> AocError {
+        AocError::Solve {
+            message: message.into(),
+        }
+    }
+
+    pub fn io(file: impl AsRef<std::path::Path>, e: std::io::Error) -> AocError {
+        AocError::Io {
+            file: file.as_ref().display().to_string(),
+            message: e.to_string(),
+        }
+    }
+
+    // The context setters below never override a more precise location set by an inner parser
+
+    pub fn in_file(mut self, file: &str) -> AocError {
+        match &mut self {
+            AocError::Parse { location, .. } if location.file.is_none() => {
+                location.file = Some(file.to_string());
+            }
+            AocError::Multiple(errors) => {
+                *errors = errors.drain(..).map(|e| e.in_file(file)).collect();
+            }
+            _ => {}
+        }
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> AocError {
+        if let AocError::Parse { location, .. } = &mut self
+            && location.line.is_none()
+        {
+            location.line = Some(line);
+        }
+        self
+    }
+
+    pub fn at_col(mut self, col: usize) -> AocError {
+        if let AocError::Parse { location, .. } = &mut self
+            && location.col.is_none()
+        {
+            location.col = Some(col);
+        }
+        self
+    }
+
+    /// Moves a line number relative to a part of the file to a line number in the whole file
+    pub fn offset_lines(mut self, offset: usize) -> AocError {
+        match &mut self {
+            AocError::Parse { location, .. } => {
+                if let Some(line) = &mut location.line {
+                    *line += offset;
+                }
+            }
+            AocError::Multiple(errors) => {
+                *errors = errors.drain(..).map(|e| e.offset_lines(offset)).collect();
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Io { file, message } => write!(f, "Could not access file {file}: {message}"),
+            AocError::InvalidPattern { pattern, message } => {
+                write!(f, "Could not parse pattern {pattern}: {message}")
+            }
+            AocError::Parse { location, message } => write!(f, "{location}: {message}"),
+            AocError::Solve { message } => write!(f, "{message}"),
+            AocError::Multiple(errors) => {
+                write!(f, "{} lines could not be parsed", errors.len())?;
+                for error in errors {
+                    write!(f, "\n{error}")?;
+                }
+                Ok(())
+            }
+            AocError::Remote { url, message } => write!(f, "Request to {url} failed: {message}"),
+            AocError::Config { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<String> for AocError {
+    fn from(message: String) -> AocError {
+        AocError::parse(message)
+    }
+}
+
+impl From<&str> for AocError {
+    fn from(message: &str) -> AocError {
+        AocError::parse(message)
+    }
+}
+
+impl From<ParseIntError> for AocError {
+    fn from(e: ParseIntError) -> AocError {
+        AocError::parse(e.to_string())
+    }
+}
+
+impl std::error::Error for AocError {}
+
+impl AocError {
+    /// rustc-style report, showing the offending line of the input with a caret under the column
+    /// The line is read back from the input file, so this is only meant for reporting
+    pub fn diagnostic(&self) -> String {
+        if let AocError::Multiple(errors) = self {
+            let diagnostics: Vec<String> = errors.iter().map(AocError::diagnostic).collect();
+            return format!(
+                "{} lines could not be parsed\n{}",
+                errors.len(),
+                diagnostics.join("\n")
+            );
+        }
+        let AocError::Parse { location, message } = self else {
+            return format!("error: {self}");
+        };
+        let source_line = match (&location.file, location.line) {
+            (Some(file), Some(line)) => input::read(file)
+                .ok()
+                .and_then(|content| content.lines().nth(line - 1).map(str::to_string)),
+            _ => None,
+        };
+        render_diagnostic(location, message, source_line.as_deref())
+    }
+}
+
+fn render_diagnostic(location: &Location, message: &str, source_line: Option<&str>) -> String {
+    let mut out = format!("error: {message}\n");
+    let (Some(line), Some(source_line)) = (location.line, source_line) else {
+        out.push_str(&format!(" --> {location}"));
+        return out;
+    };
+
+    let gutter = " ".repeat(line.to_string().len());
+    out.push_str(&format!("{gutter}--> {location}\n"));
+    out.push_str(&format!("{gutter} |\n"));
+    out.push_str(&format!("{line} | {source_line}"));
+    if let Some(col) = location.col {
+        // Keep tabs so the caret lines up with the source line
+        let padding: String = source_line
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(col - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        out.push_str(&format!("\n{gutter} | {padding}^"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_context() {
+        let error = AocError::parse("Invalid number")
+            .at_col(4)
+            .at_line(2)
+            .offset_lines(10)
+            .at_line(1)
+            .in_file("src/files/day1");
+
+        assert_eq!(error.to_string(), "src/files/day1:12:4: Invalid number");
+    }
+
+    #[test]
+    fn test_context_ignored_outside_parse_errors() {
+        let error = AocError::solve("Unable to find path")
+            .at_line(3)
+            .in_file("day12");
+        assert_eq!(error, AocError::solve("Unable to find path"));
+    }
+
+    #[test]
+    fn test_render_diagnostic() {
+        let location = Location {
+            file: Some("src/files/day13".to_string()),
+            line: Some(12),
+            col: Some(8),
+        };
+        assert_eq!(
+            render_diagnostic(&location, "Unbalanced brackets", Some("[1,[2]]]")),
+            "error: Unbalanced brackets
+  --> src/files/day13:12:8
+   |
+12 | [1,[2]]]
+   |        ^"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_without_source() {
+        let error = AocError::parse("Invalid letter D").at_col(1).at_line(3);
+        assert_eq!(
+            error.diagnostic(),
+            "error: Invalid letter D\n --> <input>:3:1"
+        );
+        assert_eq!(
+            AocError::solve("Unable to find path").diagnostic(),
+            "error: Unable to find path"
+        );
+    }
+
+    #[test]
+    fn test_multiple_errors_context() {
+        let error = AocError::Multiple(vec![
+            AocError::parse("Invalid number a").at_line(1).at_col(1),
+            AocError::parse("Invalid number b").at_line(4).at_col(3),
+        ])
+        .offset_lines(2)
+        .in_file("day1");
+
+        assert_eq!(
+            error.to_string(),
+            "2 lines could not be parsed\nday1:3:1: Invalid number a\nday1:6:3: Invalid number b"
+        );
+    }
+}
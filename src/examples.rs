@@ -0,0 +1,284 @@
+//! Examples of the puzzle statements, listed with their answers in `src/examples/day<N>.toml`
+//! build.rs generates one test per example, named `day<N>_example_<i>` and running `check`
+//!
+//! ```toml
+//! [[example]]
+//! file = "day9_1.txt"      # in src/examples, or `input = "..."` for a short input
+//! params = { rope = 2 }    # optional, see Puzzle::solve
+//! part2 = 13               # the answer of each part checked by the example
+//! ```
+
+use std::path::PathBuf;
+use std::{env, fs, process};
+
+use serde::Deserialize;
+
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::input;
+use crate::parser::parse_toml;
+use crate::solution::{Params, Solution};
+use crate::verify::Expected;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Example {
+    file: Option<String>,
+    input: Option<String>,
+    #[serde(default)]
+    params: Params,
+    part1: Option<Expected>,
+    part2: Option<Expected>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    example: Vec<Example>,
+}
+
+pub fn manifest_path(day: u8) -> String {
+    format!("src/examples/day{day}.toml")
+}
+
+/// The examples of a day, in the order of its manifest
+pub fn load(day: u8) -> Result<Vec<Example>> {
+    let file = manifest_path(day);
+    let text = input::read(&file)?;
+    let manifest: Manifest = parse_toml(&text, &file)?;
+    Ok(manifest.example)
+}
+
+// The file an example is read from, an inline input being written to a temporary directory of
+// its own that is removed once the example ran
+struct ExampleFile {
+    path: String,
+    temp_dir: Option<PathBuf>,
+}
+
+impl ExampleFile {
+    fn new(day: u8, index: usize, example: &Example) -> Result<ExampleFile> {
+        let name = format!("day{day}_{}", index + 1);
+        match (&example.file, &example.input) {
+            (Some(file), None) => Ok(ExampleFile {
+                path: format!("src/examples/{file}"),
+                temp_dir: None,
+            }),
+            (None, Some(text)) => {
+                let dir =
+                    env::temp_dir().join(format!("aoc-2022-examples-{}-{name}", process::id()));
+                fs::create_dir_all(&dir).map_err(|e| AocError::io(&dir, e))?;
+                // Removed on drop from now on, even if writing fails
+                let file = ExampleFile {
+                    path: dir.join(&name).display().to_string(),
+                    temp_dir: Some(dir),
+                };
+                fs::write(&file.path, text).map_err(|e| AocError::io(&file.path, e))?;
+                Ok(file)
+            }
+            _ => Err(AocError::parse(format!(
+                "Example {} needs either a file or an input",
+                index + 1
+            ))
+            .in_file(&manifest_path(day))),
+        }
+    }
+}
+
+impl Drop for ExampleFile {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.temp_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// A part checked by an example
+#[derive(Debug)]
+pub struct ExampleCheck {
+    /// Numbered from 1, in the order of the manifest
+    pub example: usize,
+    pub part: u8,
+    pub expected: Expected,
+    pub answer: Result<Answer>,
+}
+
+impl ExampleCheck {
+    pub fn passed(&self) -> bool {
+        matches!(&self.answer, Ok(answer) if self.expected.matches(answer))
+    }
+}
+
+/// Solves every part that an example gives the answer of
+pub fn run_example(solution: &Solution, index: usize, example: &Example) -> Vec<ExampleCheck> {
+    let file = ExampleFile::new(solution.day, index, example);
+    let expected = [(1, &example.part1), (2, &example.part2)];
+    let mut checks = vec![];
+    for (part, expected) in expected {
+        let Some(expected) = expected else {
+            continue;
+        };
+        let answer = match &file {
+            Ok(file) => solution
+                .run_on(&file.path, &[part], &example.params)
+                .0
+                .remove(0),
+            Err(e) => Err(e.clone()),
+        };
+        checks.push(ExampleCheck {
+            example: index + 1,
+            part,
+            expected: expected.clone(),
+            answer,
+        });
+    }
+    checks
+}
+
+pub fn run_examples(solution: &Solution) -> Result<Vec<ExampleCheck>> {
+    let examples = load(solution.day)?;
+    Ok(examples
+        .iter()
+        .enumerate()
+        .flat_map(|(index, example)| run_example(solution, index, example))
+        .collect())
+}
+
+// Expected lines prefixed by -, answered ones by +, and common ones by two spaces
+fn line_diff(expected: &str, answer: &str) -> Vec<String> {
+    let expected: Vec<&str> = expected.trim().lines().collect();
+    let answer: Vec<&str> = answer.trim().lines().collect();
+    let mut diff = vec![];
+    for i in 0..expected.len().max(answer.len()) {
+        match (expected.get(i), answer.get(i)) {
+            (Some(e), Some(a)) if e == a => diff.push(format!("  {e}")),
+            (e, a) => {
+                diff.extend(e.map(|e| format!("- {e}")));
+                diff.extend(a.map(|a| format!("+ {a}")));
+            }
+        }
+    }
+    diff
+}
+
+/// One line per check, failures are followed by the diff of the expected and given answers
+pub fn render_checks(checks: &[ExampleCheck]) -> String {
+    let mut lines = vec![];
+    for check in checks {
+        let name = format!("Example {}, part {}", check.example, check.part);
+        match &check.answer {
+            Ok(answer) if check.passed() => {
+                let answer = answer.to_string();
+                match answer.lines().count() {
+                    1 => lines.push(format!("{name}: ok, {answer}")),
+                    _ => lines.push(format!("{name}: ok")),
+                }
+            }
+            Ok(answer) => {
+                lines.push(format!("{name}: FAIL"));
+                let diff = line_diff(&check.expected.to_string(), &answer.to_string());
+                lines.extend(diff.into_iter().map(|line| format!("    {line}")));
+            }
+            Err(e) => {
+                lines.push(format!("{name}: ERROR, expected {}", check.expected));
+                lines.extend(e.to_string().lines().map(|line| format!("    {line}")));
+            }
+        }
+    }
+    let passed = checks.iter().filter(|check| check.passed()).count();
+    lines.push(format!("{passed} passed, {} failed", checks.len() - passed));
+    lines.join("\n")
+}
+
+/// Runs an example and panics with every part that does not give the expected answer
+#[cfg(test)]
+pub fn check(day: u8, index: usize) {
+    let mut examples = load(day).unwrap_or_else(|e| panic!("{e}"));
+    let example = examples.remove(index);
+    let solution = crate::exercises::solutions()
+        .into_iter()
+        .find(|s| s.day == day)
+        .unwrap_or_else(|| panic!("Day {day} is not registered"));
+
+    let checks = run_example(&solution, index, &example);
+    let failures: Vec<String> = checks
+        .iter()
+        .filter(|check| !check.passed())
+        .map(|check| match &check.answer {
+            Ok(answer) => format!(
+                "part {}: expected {}, got {answer}",
+                check.part, check.expected
+            ),
+            Err(e) => format!("part {}: {}", check.part, e.diagnostic()),
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "Example {} of day {day} failed\n{}",
+        index + 1,
+        failures.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod generated {
+    use super::check;
+
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
+
+/// Checks the examples of a day against the answers of its manifest and prints render_checks
+/// Returns whether every example passed
+pub fn check_examples(solution: &Solution) -> bool {
+    match run_examples(solution) {
+        Ok(checks) => {
+            println!("{}", render_checks(&checks));
+            checks.iter().all(ExampleCheck::passed)
+        }
+        Err(e) => {
+            eprintln!("Day {}: {}", solution.day, e.diagnostic());
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_render_checks() {
+        let check = |example, part, expected, answer| ExampleCheck {
+            example,
+            part,
+            expected,
+            answer,
+        };
+        let screen = |rows: &[&str]| {
+            Answer::Grid(
+                rows.iter()
+                    .map(|row| row.chars().map(|c| c == '#').collect())
+                    .collect(),
+            )
+        };
+        let checks = [
+            check(1, 1, Expected::Integer(24000), Ok(Answer::Integer(24000))),
+            check(1, 2, Expected::Integer(45000), Ok(Answer::Integer(45001))),
+            check(
+                2,
+                2,
+                Expected::Text("##..\n#..#\n.##.".to_string()),
+                Ok(screen(&["##..", "#.##", ".##."])),
+            ),
+            check(
+                3,
+                1,
+                Expected::Text("CMZ".to_string()),
+                Err(AocError::solve("Empty stack 3")),
+            ),
+        ];
+        assert_snapshot!(render_checks(&checks));
+    }
+}
@@ -1,84 +1,71 @@
-use crate::parser::{parse_aoc_file, parse_into_struct, parse_until_pattern};
-
-pub fn day_1_1(filename: &str) -> i32 {
-    let mut remaining = parse_aoc_file(filename, None);
-    let mut max = 0;
-    loop {
-        let (matched, rest) = parse_until_pattern(remaining, "^$");
-
-        let total: i32 = parse_into_struct(matched, |s| {
-            s.parse::<i32>()
-                .unwrap_or_else(|_| panic!("Unable to parse number, found {s} instead."))
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{parse_blocks, read_aoc_file};
+use crate::solution::Puzzle;
+
+// Sums the calories of every elf, elves being separated by blank lines
+fn parse_calories(input: &str) -> Result<Vec<i32>> {
+    parse_blocks(input)
+        .into_iter()
+        .map(|block| {
+            (block.first_line..)
+                .zip(block.lines)
+                .map(|(line, s)| {
+                    s.parse::<i32>().map_err(|_| {
+                        AocError::parse(format!("Unable to parse number, found {s} instead."))
+                            .at_line(line)
+                            .at_col(1)
+                    })
+                })
+                .sum()
         })
-        .iter()
-        .sum();
-
-        if total > max {
-            max = total;
-        }
-
-        if rest.is_empty() {
-            break;
-        } else {
-            // Ignore first element of rest, it's an empty string, otherwise it will loop infinitly
-            remaining = rest[1..].to_vec();
-        }
-    }
-
-    max
+        .collect()
 }
 
-pub fn day_1_2(filename: &str) -> i32 {
-    let mut remaining = parse_aoc_file(filename, None);
-    let mut ordered: Vec<i32> = vec![];
+pub struct Day1;
 
-    loop {
-        let (matched, rest) = parse_until_pattern(remaining, "^$");
+impl Puzzle for Day1 {
+    // Total calories carried by each elf
+    type Input = Vec<i32>;
 
-        let total: i32 = parse_into_struct(matched, |s| {
-            s.parse::<i32>()
-                .unwrap_or_else(|_| panic!("Unable to parse number, found {s} instead."))
-        })
-        .iter()
-        .sum();
-
-        let mut i = 0;
-        loop {
-            if i == ordered.len() {
-                break;
-            }
+    fn parse(filename: &str) -> Result<Vec<i32>> {
+        parse_calories(&read_aoc_file(filename)?)
+    }
 
-            if ordered[i] > total {
-                i += 1;
-            } else {
-                break;
-            }
-        }
-        ordered.insert(i, total);
+    fn part1(totals: &Vec<i32>) -> Result<Answer> {
+        Ok(totals.iter().max().copied().unwrap_or(0).into())
+    }
 
-        if rest.is_empty() {
-            break;
-        } else {
-            // Ignore first element of rest, it's an empty string, otherwise it will loop infinitly
-            remaining = rest[1..].to_vec();
+    fn part2(totals: &Vec<i32>) -> Result<Answer> {
+        if totals.len() < 3 {
+            return Err(AocError::solve(format!(
+                "Expected at least 3 elves, found {}",
+                totals.len()
+            )));
         }
+        let mut ordered = totals.clone();
+        ordered.sort_by(|a, b| b.cmp(a));
+        Ok(ordered[0..3].iter().sum::<i32>().into())
     }
-
-    ordered[0..3].iter().sum()
 }
 
+#[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
-    fn test_day_1_1() {
-        let res = super::day_1_1("./src/files/day1.1.test");
+    fn test_parse() {
+        let totals = Day1::parse("src/examples/day1.txt").unwrap();
 
-        assert_eq!(res, 21);
+        assert_eq!(totals, vec![3, 2, 21, 15]);
     }
 
     #[test]
-    fn test_day_1_2() {
-        let res = super::day_1_2("./src/files/day1.1.test");
+    fn test_parse_large_input() {
+        let input = "1000\n2000\n3000\n\n".repeat(100_000);
+        let totals = parse_calories(&input).unwrap();
 
-        assert_eq!(res, 39);
+        assert_eq!(totals.len(), 100_000);
+        assert!(totals.iter().all(|total| *total == 6000));
     }
 }
@@ -1,47 +1,39 @@
-use core::panic;
-
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::grid::Grid;
+use crate::parser::{lines, parse_into_struct_with, read_aoc_file};
+use crate::solution::Puzzle;
 
 #[derive(Debug)]
-enum Operation {
+pub enum Operation {
     Addx(i32),
     Noop,
 }
 
-fn parse_file(filename: &str) -> Vec<Operation> {
-    let lines = parse_aoc_file(filename, None);
-    lines
-        .iter()
-        .enumerate()
-        .flat_map(|(i, l)| {
-            let splits = l.split(' ').collect::<Vec<&str>>();
-            let Some(op) = splits.first() else {
-            panic!("Can't read operation {l} at line {i}");
+fn parse_file(filename: &str) -> Result<Vec<Operation>> {
+    let input = read_aoc_file(filename)?;
+    let operations = parse_into_struct_with(lines(&input), |l| {
+        let (op, value) = match l.split_once(' ') {
+            Some((op, value)) => (op, Some(value)),
+            None => (l, None),
         };
-            let value = splits.get(1);
-            if *op != "addx" && value.is_some() {
-                panic!("Unknown operation {op} at line {i}");
-            }
 
-            match *op {
-                "addx" => vec![
-                    Operation::Noop,
-                    Operation::Addx(value.unwrap().parse::<i32>().unwrap_or_else(|_| {
-                        panic!(
-                            "Can't parse value {} at line {}",
-                            value.unwrap_or(&"NONE"),
-                            i
-                        )
-                    })),
-                ],
-                "noop" => vec![Operation::Noop],
-                _ => panic!("Unknown operation {op} at line {i}"),
+        match (op, value) {
+            ("addx", Some(value)) => {
+                let value = value.parse::<i32>().map_err(|_| {
+                    AocError::parse(format!("Can't parse value {value}")).at_col(op.len() + 2)
+                })?;
+                Ok(vec![Operation::Noop, Operation::Addx(value)])
             }
-        })
-        .collect()
+            ("addx", None) => Err(AocError::parse("Missing value for addx").at_col(1)),
+            ("noop", None) => Ok(vec![Operation::Noop]),
+            _ => Err(AocError::parse(format!("Unknown operation {l}")).at_col(1)),
+        }
+    })?;
+    Ok(operations.into_iter().flatten().collect())
 }
 
-fn run_operations(operations: Vec<Operation>, signal_start: u32, signal_step: u32) -> i32 {
+fn run_operations(operations: &[Operation], signal_start: u32, signal_step: u32) -> i32 {
     let mut iter = 0;
     let mut register: i32 = 1;
 
@@ -62,63 +54,44 @@ fn run_operations(operations: Vec<Operation>, signal_start: u32, signal_step: u3
 
 // works only with parse_file_v2
 fn run_operations_screen(
-    operations: Vec<Operation>,
-    signal_size: i32,
-    screen_height: u32,
-) -> Vec<Vec<bool>> {
+    operations: &[Operation],
+    signal_size: usize,
+    screen_height: usize,
+) -> Grid<bool> {
     let mut register: i32 = 1;
-    let mut screen: Vec<Vec<bool>> = vec![];
+    let mut screen = Grid::filled(signal_size, screen_height, false);
     let mut ops = operations.iter().cycle();
 
-    for _ in 0..screen_height {
-        let line: Vec<bool> = (0..signal_size)
-            .map(|i| {
-                let mut value = false;
-
-                // safe unwrap as ops is a cycle
-                let op = ops.next().unwrap();
-                if i == register - 1 || i == register || i == register + 1 {
-                    value = true;
-                }
-                if let Operation::Addx(v) = op {
-                    register += v;
-                }
-                value
-            })
-            .collect();
-        screen.push(line);
+    for pixel in screen.positions() {
+        // safe unwrap as ops is a cycle
+        let op = ops.next().unwrap();
+        let i = pixel.x as i32;
+        screen[pixel] = i == register - 1 || i == register || i == register + 1;
+        if let Operation::Addx(v) = op {
+            register += v;
+        }
     }
 
     screen
 }
 
-pub fn day_10_1(filename: &str) -> i32 {
-    let operations = parse_file(filename);
-    run_operations(operations, 20, 40)
-}
-
-pub fn day_10_2(filename: &str) -> Vec<Vec<bool>> {
-    let operations = parse_file(filename);
-    run_operations_screen(operations, 40, 6)
-}
+pub struct Day10;
 
-#[cfg(test)]
-mod tests {
-    use insta::assert_debug_snapshot;
+impl Puzzle for Day10 {
+    type Input = Vec<Operation>;
 
-    use super::*;
+    fn parse(filename: &str) -> Result<Vec<Operation>> {
+        parse_file(filename)
+    }
 
-    #[test]
-    fn test_exec_op() {
-        let operations = parse_file("src/files/day10_1.test");
-        let total = run_operations(operations, 20, 40);
-        assert_eq!(total, 13140);
+    fn part1(operations: &Vec<Operation>) -> Result<Answer> {
+        Ok(run_operations(operations, 20, 40).into())
     }
 
-    #[test]
-    fn test_exec_op_screen() {
-        let operations = parse_file("src/files/day10_1.test");
-        let screen = run_operations_screen(operations, 40, 6);
-        assert_debug_snapshot!(screen);
+    fn part2(operations: &Vec<Operation>) -> Result<Answer> {
+        if operations.is_empty() {
+            return Err(AocError::solve("No operation to draw the screen with"));
+        }
+        Ok(run_operations_screen(operations, 40, 6).into())
     }
 }
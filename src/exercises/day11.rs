@@ -1,8 +1,10 @@
-use core::panic;
+use std::num::NonZero;
+use std::str::FromStr;
 
-use regex::Regex;
-
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{AocParse, parse_blocks, read_aoc_file};
+use crate::solution::Puzzle;
 
 type Number = u64;
 type UnsignedNumber = i64;
@@ -19,34 +21,45 @@ enum OperationValue {
     Number(Number),
 }
 
-#[derive(Debug, Clone)]
-struct Monkey {
+// Reads an operation such as "* 19" or "+ old"
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Operation, String> {
+        let Some((operator, value)) = s.split_once(' ') else {
+            return Err(format!("expected an operator and a value, found {s}"));
+        };
+        let value = match value {
+            "old" => OperationValue::Old,
+            n => OperationValue::Number(n.parse().map_err(|e| format!("{e}"))?),
+        };
+        match operator {
+            "+" => Ok(Operation::Add(value)),
+            "*" => Ok(Operation::Mul(value)),
+            _ => Err(format!("unknown operator {operator}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, AocParse)]
+#[aoc(regex = r"Monkey \d+:
+  Starting items: ([0-9, ]+)
+  Operation: new = old ([+*] (?:\d+|old))
+  Test: divisible by (\d+)
+    If true: throw to monkey (\d+)
+    If false: throw to monkey (\d+)")]
+pub struct Monkey {
+    #[aoc(split = ", ")]
     items: Vec<Number>,
     operation: Operation,
-    test_divisible_by: Number,
+    test_divisible_by: NonZero<Number>,
     true_monkey: Number,
     false_monkey: Number,
+    #[aoc(skip)]
     pub inspected: Number,
 }
 
 impl Monkey {
-    fn new(
-        items: Vec<Number>,
-        operation: Operation,
-        test_divisible_by: Number,
-        true_monkey: Number,
-        false_monkey: Number,
-    ) -> Monkey {
-        Monkey {
-            items,
-            operation,
-            test_divisible_by,
-            true_monkey,
-            false_monkey,
-            inspected: 0,
-        }
-    }
-
     fn has_items(&self) -> bool {
         !self.items.is_empty()
     }
@@ -77,7 +90,7 @@ impl Monkey {
             },
         } / divider;
         worry %= total_mod;
-        if worry % self.test_divisible_by == 0 {
+        if worry.is_multiple_of(self.test_divisible_by.get()) {
             (worry, self.true_monkey)
         } else {
             (worry, self.false_monkey)
@@ -85,92 +98,58 @@ impl Monkey {
     }
 }
 
-fn parse_file(filename: &str) -> Vec<Monkey> {
-    let raw = parse_aoc_file(filename, Some("\n\n"));
-    let Ok(regex) = Regex::new(r"Monkey \d+:
-  Starting items: ([0-9, ]+)
-  Operation: new = old (\+|\*) (\d+|old)
-  Test: divisible by (\d+)
-    If true: throw to monkey (\d+)
-    If false: throw to monkey (\d+)") else {
-        panic!("Can't compile regex");
-    };
-
-    raw.iter()
-        .map(|r| {
-            let captures = regex
-                .captures(r)
-                .unwrap_or_else(|| panic!("Failed parsing {r}"));
-            let items = captures
-                .get(1)
-                .unwrap_or_else(|| panic!("Can't read items list from {r}"))
-                .as_str()
-                .split(", ")
-                .map(|s| {
-                    s.parse::<Number>()
-                        .unwrap_or_else(|_| panic!("Can't parse number {s} in items list"))
-                })
-                .collect::<Vec<Number>>();
-            let operation_value = match captures
-                .get(3)
-                .unwrap_or_else(|| panic!("Can't read operation value from {r}"))
-                .as_str()
-            {
-                "old" => OperationValue::Old,
-                _ => OperationValue::Number(
-                    captures
-                        .get(3)
-                        .unwrap_or_else(|| panic!("Can't read operation number from {r}"))
-                        .as_str()
-                        .parse::<Number>()
-                        .unwrap_or_else(|_| panic!("Can't parse operation number from {r}")),
-                ),
-            };
-            let operation = match captures
-                .get(2)
-                .unwrap_or_else(|| panic!("Can't read operation from {r}"))
-                .as_str()
-            {
-                "+" => Operation::Add(operation_value),
-                "*" => Operation::Mul(operation_value),
-                _ => panic!("Unknown operation"),
+fn parse_file(filename: &str) -> Result<Vec<Monkey>> {
+    parse_monkeys(&read_aoc_file(filename)?)
+}
+
+// Monkeys are separated by blank lines, and each throws to two other monkeys
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey>> {
+    let blocks = parse_blocks(input);
+    let mut monkeys = vec![];
+    for block in &blocks {
+        let monkey = Monkey::aoc_parse(&block.lines.join("\n"))
+            .map_err(|e| e.offset_lines(block.first_line - 1))?;
+        monkeys.push(monkey);
+    }
+
+    for (i, monkey) in monkeys.iter().enumerate() {
+        // "If true" and "If false" are the 5th and 6th lines of a monkey
+        for (line, target) in [(5, monkey.true_monkey), (6, monkey.false_monkey)] {
+            // A monkey throwing to itself would never be done with its items
+            let message = if target as usize >= monkeys.len() {
+                format!("Monkey {i} throws to unknown monkey {target}")
+            } else if target as usize == i {
+                format!("Monkey {i} throws to itself")
+            } else {
+                continue;
             };
-            let test_divisible_by = captures
-                .get(4)
-                .unwrap_or_else(|| panic!("Can't read divisble_by from {r}"))
-                .as_str()
-                .parse::<Number>()
-                .unwrap();
-            let true_monkey = captures
-                .get(5)
-                .unwrap_or_else(|| panic!("Can't read if true number from {r}"))
-                .as_str()
-                .parse::<Number>()
-                .unwrap();
-            let false_monkey = captures
-                .get(6)
-                .unwrap_or_else(|| panic!("Can't read if false number from {r}"))
-                .as_str()
-                .parse::<Number>()
-                .unwrap();
-
-            Monkey::new(
-                items,
-                operation,
-                test_divisible_by,
-                true_monkey,
-                false_monkey,
-            )
-        })
-        .collect()
+            let col = blocks[i]
+                .lines
+                .get(line - 1)
+                .map_or(1, |l| l.len() - target.to_string().len() + 1);
+            return Err(AocError::parse(message)
+                .at_line(blocks[i].first_line - 1 + line)
+                .at_col(col));
+        }
+    }
+
+    Ok(monkeys)
 }
 
 fn compute_monkeys(
-    monkeys: &mut Vec<Monkey>,
+    monkeys: &mut [Monkey],
     rounds: Number,
     worry_divider: Option<Number>,
-) -> Number {
-    let total_mod = monkeys.iter().fold(1, |acc, m| acc * m.test_divisible_by);
+) -> Result<Number> {
+    if monkeys.len() < 2 {
+        return Err(AocError::solve(format!(
+            "Expected at least 2 monkeys, found {}",
+            monkeys.len()
+        )));
+    }
+    let total_mod = monkeys
+        .iter()
+        .fold(1, |acc, m| acc * m.test_divisible_by.get());
 
     for _ in 0..rounds {
         for i in 0..monkeys.len() {
@@ -182,42 +161,64 @@ fn compute_monkeys(
     }
 
     monkeys.sort_by_key(|m| 0 - m.inspected as UnsignedNumber);
-    monkeys[0].inspected * monkeys[1].inspected
+    Ok(monkeys[0].inspected * monkeys[1].inspected)
 }
 
-pub fn day_11_1(filename: &str) -> Number {
-    let mut monkeys = parse_file(filename);
-    compute_monkeys(&mut monkeys, 20, None)
-}
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = Vec<Monkey>;
 
-pub fn day_11_2(filename: &str) -> Number {
-    let mut monkeys = parse_file(filename);
-    compute_monkeys(&mut monkeys, 10000, Some(1))
+    fn parse(filename: &str) -> Result<Vec<Monkey>> {
+        parse_file(filename)
+    }
+
+    fn part1(monkeys: &Vec<Monkey>) -> Result<Answer> {
+        compute_monkeys(&mut monkeys.clone(), 20, None)?.try_into()
+    }
+
+    fn part2(monkeys: &Vec<Monkey>) -> Result<Answer> {
+        compute_monkeys(&mut monkeys.clone(), 10000, Some(1))?.try_into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
 
-    use crate::exercises::day11::{compute_monkeys, day_11_2, parse_file};
-
-    use super::day_11_1;
+    use super::*;
 
     #[test]
     fn test_parse_monkeys() {
-        let monkeys = parse_file("src/files/day11_1.test");
+        let monkeys = parse_file("src/examples/day11.txt").unwrap();
         assert_debug_snapshot!(monkeys);
     }
 
     #[test]
-    fn test_day_11_1() {
-        let monkeys = day_11_1("src/files/day11_1.test");
-        assert_eq!(monkeys, 10605);
-    }
-
-    #[test]
-    fn test_day_11_2() {
-        let monkeys = day_11_2("src/files/day11_1.test");
-        assert_eq!(monkeys, 2713310158);
+    fn test_parse_invalid_target() {
+        let input = read_aoc_file("src/examples/day11.txt").unwrap();
+        let to_itself = input.replacen(
+            "If true: throw to monkey 2",
+            "If true: throw to monkey 0",
+            1,
+        );
+        assert_eq!(
+            parse_monkeys(&to_itself).unwrap_err(),
+            AocError::parse("Monkey 0 throws to itself")
+                .at_line(5)
+                .at_col(30)
+        );
+
+        let unknown = input.replacen(
+            "If false: throw to monkey 3",
+            "If false: throw to monkey 4",
+            1,
+        );
+        assert_eq!(
+            parse_monkeys(&unknown).unwrap_err(),
+            AocError::parse("Monkey 0 throws to unknown monkey 4")
+                .at_line(6)
+                .at_col(31)
+        );
     }
 }
@@ -1,124 +1,91 @@
 use pathfinding::prelude::dijkstra;
 
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::parser::read_aoc_file;
+use crate::solution::Puzzle;
 
-type Array = Vec<Vec<char>>;
-type Position = (usize, usize);
+pub type Array = Grid<char>;
 
-fn parse_file(filename: &str) -> Array {
-    parse_aoc_file(filename, None)
-        .iter()
-        .map(|l| l.chars().collect())
-        .collect()
+fn parse_file(filename: &str) -> Result<Array> {
+    Grid::parse(&read_aoc_file(filename)?, |c| {
+        if c.is_ascii_lowercase() || c == 'S' || c == 'E' {
+            Ok(c)
+        } else {
+            Err(format!("Invalid elevation {c}"))
+        }
+    })
 }
 
 fn is_next(current: char, next: char) -> bool {
     match current {
         'S' => next == 'a',
         'a' => (next == 'a' || next == 'b') && next != 'S' && next != 'E',
-        'z' => next <= 'z' || next == 'E',
+        // E has the elevation of z, so it can be reached from y as well
+        'y' | 'z' => next <= 'z' && next != 'S',
         c => (next <= c || next as u32 == c as u32 + 1) && next != 'S' && next != 'E',
     }
 }
 
-fn find_start(array: &Array) -> Option<Position> {
-    for (x, row) in array.iter().enumerate() {
-        for (y, c) in row.iter().enumerate() {
-            if *c == 'S' {
-                return Some((x, y));
-            }
-        }
-    }
-    None
+fn neighbours(position: Point, array: &Array) -> impl Iterator<Item = Point> + '_ {
+    array
+        .neighbours4(position)
+        .filter(move |next| is_next(array[position], array[*next]))
 }
 
-fn find_end(array: &Array) -> Option<Position> {
-    for (x, row) in array.iter().enumerate() {
-        for (y, c) in row.iter().enumerate() {
-            if *c == 'E' {
-                return Some((x, y));
-            }
-        }
-    }
-    None
-}
+pub struct Day12;
 
-fn neighbours((x, y): &Position, array: &Array) -> Vec<Position> {
-    let mut neighbours: Vec<Position> = Vec::new();
-    if *x > 0 && is_next(array[*x][*y], array[*x - 1][*y]) {
-        neighbours.push((*x - 1, *y));
-    }
-    if *x < array.len() - 1 && is_next(array[*x][*y], array[*x + 1][*y]) {
-        neighbours.push((*x + 1, *y));
-    }
-    if *y > 0 && is_next(array[*x][*y], array[*x][*y - 1]) {
-        neighbours.push((*x, *y - 1));
-    }
-    if *y < array[0].len() - 1 && is_next(array[*x][*y], array[*x][*y + 1]) {
-        neighbours.push((*x, *y + 1));
+impl Puzzle for Day12 {
+    type Input = Array;
+
+    fn parse(filename: &str) -> Result<Array> {
+        parse_file(filename)
     }
-    neighbours
-}
 
-pub fn day_12_1(filename: &str) -> u32 {
-    let array = parse_file(filename);
-    let start = find_start(&array).unwrap_or_else(|| panic!("Unable to find start"));
-    let end = find_end(&array).unwrap_or_else(|| panic!("Unable to find end"));
+    fn part1(array: &Array) -> Result<Answer> {
+        let start = array
+            .position(|c| *c == 'S')
+            .ok_or_else(|| AocError::solve("Unable to find start"))?;
+        let end = array
+            .position(|c| *c == 'E')
+            .ok_or_else(|| AocError::solve("Unable to find end"))?;
 
-    let result = dijkstra(
-        &start,
-        |&p| neighbours(&p, &array).into_iter().map(|p| (p, 1)),
-        |&p| p == end,
-    );
-    result.unwrap_or_else(|| panic!("Unable to find path")).1
-}
+        let result = dijkstra(
+            &start,
+            |&p| neighbours(p, array).map(|p| (p, 1)),
+            |&p| p == end,
+        );
+        let (_, steps) = result.ok_or_else(|| AocError::solve("Unable to find path"))?;
+        Ok(steps.into())
+    }
 
-// quick and dirty
-pub fn day_12_2(filename: &str) -> u32 {
-    // replace S by 'a'
-    let array = parse_file(filename)
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|c| if *c == 'S' { 'a' } else { *c })
-                .collect()
-        })
-        .collect();
-    let end = find_end(&array).unwrap_or_else(|| panic!("Unable to find end"));
-    let mut min = u32::MAX;
+    // quick and dirty
+    fn part2(array: &Array) -> Result<Answer> {
+        // replace S by 'a'
+        let array = array.map(|c| if *c == 'S' { 'a' } else { *c });
+        let end = array
+            .position(|c| *c == 'E')
+            .ok_or_else(|| AocError::solve("Unable to find end"))?;
+        let mut min = u32::MAX;
 
-    for i in 0..array.len() {
-        for j in 0..array[0].len() {
-            if array[i][j] == 'a' {
-                let start = (i, j);
-                let result = dijkstra(
-                    &start,
-                    |&p| neighbours(&p, &array).into_iter().map(|p| (p, 1)),
-                    |&p| p == end,
-                );
-                if let Some((_, steps)) = result {
-                    if steps < min {
-                        min = steps;
-                    }
-                }
+        for start in array.positions().filter(|p| array[*p] == 'a') {
+            let result = dijkstra(
+                &start,
+                |&p| neighbours(p, &array).map(|p| (p, 1)),
+                |&p| p == end,
+            );
+            if let Some((_, steps)) = result
+                && steps < min
+            {
+                min = steps;
             }
         }
-    }
 
-    min
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_day_12_1() {
-        assert_eq!(day_12_1("src/files/day12_1.test"), 31);
-    }
-
-    #[test]
-    fn test_day_12_2() {
-        assert_eq!(day_12_2("src/files/day12_1.test"), 29);
+        if min == u32::MAX {
+            return Err(AocError::solve("Unable to find path"));
+        }
+        Ok(min.into())
     }
 }
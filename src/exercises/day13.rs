@@ -1,9 +1,18 @@
 use std::cmp::Ordering;
-
-use crate::parser::parse_aoc_file;
-
+use std::str::FromStr;
+
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::combinators::{
+    PResult, delimited, either, integer, literal, map, parse_complete, separated,
+};
+use crate::parser::{parse_blocks, read_aoc_file};
+use crate::solution::Puzzle;
+
+/// An integer or a list of packets, ordered as the puzzle compares them
+/// An integer compared with a list is compared as a list of that integer
 #[derive(Debug, Clone)]
-enum Packet {
+pub enum Packet {
     Int(i32),
     Array(Vec<Packet>),
 }
@@ -11,21 +20,12 @@ enum Packet {
 // it would be a good exercise to implement the Iterator trait for Packet and would simplify the code
 
 impl Packet {
-    fn from_vec(v: Vec<Packet>) -> Self {
-        Self::Array(v)
-    }
     fn slice(&self, i: usize, j: usize) -> Packet {
         match self {
             Self::Int(_) => panic!("Cannot slice an integer"),
             Self::Array(a) => Self::Array(a[i..j].to_vec()),
         }
     }
-    fn first(&self) -> Packet {
-        match self {
-            Self::Int(_) => panic!("Cannot flatten an integer"),
-            Self::Array(a) => a.first().unwrap_or(&Self::Array(vec![])).clone(),
-        }
-    }
 }
 
 impl PartialEq for Packet {
@@ -42,134 +42,129 @@ impl PartialEq for Packet {
 }
 
 impl PartialOrd for Packet {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
-            (Packet::Int(i), Packet::Int(j)) => i.partial_cmp(j),
+            (Packet::Int(i), Packet::Int(j)) => i.cmp(j),
             (Packet::Array(a), Packet::Array(b)) => {
                 if a.is_empty() && b.is_empty() {
-                    return Some(Ordering::Equal);
+                    return Ordering::Equal;
                 }
                 let Some(a_item) = a.first() else {
-                    return Some(Ordering::Less);
+                    return Ordering::Less;
                 };
                 let Some(b_item) = b.first() else {
-                    return Some(Ordering::Greater);
+                    return Ordering::Greater;
                 };
-                match a_item.partial_cmp(b_item) {
-                    Some(Ordering::Equal) => {
-                        self.slice(1, a.len()).partial_cmp(&other.slice(1, b.len()))
-                    }
-                    Some(Ordering::Less) => Some(Ordering::Less),
-                    Some(Ordering::Greater) => Some(Ordering::Greater),
-                    None => None,
+                match a_item.cmp(b_item) {
+                    Ordering::Equal => self.slice(1, a.len()).cmp(&other.slice(1, b.len())),
+                    ordering => ordering,
                 }
             }
-            (Packet::Array(a), Packet::Int(i)) => a.partial_cmp(&vec![Packet::Int(*i)]),
-            (Packet::Int(i), Packet::Array(a)) => vec![Packet::Int(*i)].partial_cmp(a),
+            (Packet::Array(a), Packet::Int(i)) => a.cmp(&vec![Packet::Int(*i)]),
+            (Packet::Int(i), Packet::Array(a)) => vec![Packet::Int(*i)].cmp(a),
         }
     }
 }
 
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // safe unwrap because we theoretically never return None (as i32.partial_cmp(i32) never does)
-        self.partial_cmp(other).unwrap()
-    }
+impl Eq for Packet {}
+
+// A packet is an integer or a list of packets between brackets
+fn packet(input: &str) -> PResult<'_, Packet> {
+    either(
+        map(integer, Packet::Int),
+        map(
+            delimited(literal("["), separated(packet, literal(",")), literal("]")),
+            Packet::Array,
+        ),
+    )(input)
 }
 
-impl Eq for Packet {}
+fn parse_line(line: &str) -> Result<Packet> {
+    parse_complete(line, packet)
+}
+
+impl FromStr for Packet {
+    type Err = AocError;
 
-fn parse_file(filename: &str) -> Vec<String> {
-    parse_aoc_file(filename, Some("\n\n"))
+    fn from_str(s: &str) -> Result<Packet> {
+        parse_line(s)
+    }
 }
 
-fn parse_line(line: String) -> Vec<Packet> {
-    let mut curr: Vec<Packet> = Vec::new();
-    let mut stack: Vec<Vec<Packet>> = Vec::new();
-    let mut current_number: String = String::new();
-    let mut col = 1;
-
-    for c in line.chars() {
-        match c {
-            '[' => {
-                stack.push(curr);
-                curr = Vec::new();
-            }
-            ']' => {
-                let Some(mut last) = stack.pop() else {
-                    panic!("Unbalanced brackets at col {col}");
-                };
-                if !current_number.is_empty() {
-                    let Ok(number) = current_number.parse() else {
-                        panic!("Invalid number {current_number} read after ']' at col {col}");
-                    };
-                    curr.push(Packet::Int(number));
-                    current_number = String::new();
-                }
-                last.push(Packet::Array(curr));
-                curr = last;
-            }
-            ',' => {
-                if !current_number.is_empty() {
-                    let Ok(number) = current_number.parse() else {
-                        panic!("Invalid number {current_number} read after ',' at col {col}");
-                    };
-                    curr.push(Packet::Int(number));
-                    current_number = String::new();
-                }
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    // Every packet of the file, in order, pairs being consecutive packets
+    type Input = Vec<Packet>;
+
+    fn parse(filename: &str) -> Result<Vec<Packet>> {
+        let input = read_aoc_file(filename)?;
+        let mut packets = vec![];
+        for block in parse_blocks(&input) {
+            if block.lines.len() != 2 {
+                return Err(AocError::parse(format!(
+                    "Expected a pair of packets, found {} packets",
+                    block.lines.len()
+                ))
+                .at_line(block.first_line)
+                .at_col(1));
             }
-            _ => {
-                current_number.push(c);
+            for (line, l) in (block.first_line..).zip(block.lines) {
+                packets.push(parse_line(l).map_err(|e| e.at_line(line))?);
             }
         }
-        col += 1;
+        Ok(packets)
     }
 
-    curr
-}
-
-fn is_right_order(pair: &str) -> bool {
-    let lines = pair.split('\n');
-    lines.map(|line| parse_line(line.to_string())).is_sorted()
-}
+    fn part1(packets: &Vec<Packet>) -> Result<Answer> {
+        packets
+            .chunks(2)
+            .enumerate()
+            .fold(
+                0usize,
+                |acc, (i, pair)| {
+                    if pair.is_sorted() { acc + i + 1 } else { acc }
+                },
+            )
+            .try_into()
+    }
 
-pub fn day_13_1(filename: &str) -> usize {
-    let pairs = parse_file(filename);
-    pairs.iter().enumerate().fold(0, |acc, (i, pair)| {
-        if is_right_order(pair) {
-            acc + i + 1
-        } else {
-            acc
-        }
-    })
-}
+    fn part2(packets: &Vec<Packet>) -> Result<Answer> {
+        let mut packets = packets.clone();
+        packets.sort();
 
-pub fn day_13_2(filename: &str) -> i32 {
-    let mut packets: Vec<Packet> = parse_aoc_file(filename, None)
-        .iter()
-        .filter(|l| !l.is_empty())
-        // not ideal as we put everything in an array to take it out after, but i don't want to debug the parser
-        .map(|l| Packet::from_vec(parse_line(l.clone())).first())
-        .collect();
-    packets.sort();
-
-    let (div_x, div_y) = packets
-        .iter()
-        .enumerate()
-        .fold((-1, -1), |(x, y), (i, packet)| {
-            if let Packet::Array(a) = packet && a.len() == 1 && let Packet::Array(b) = a.first().unwrap() && b.len() == 1 {
-                if let Packet::Int(value) = b.first().unwrap() {
+        let (div_x, div_y) = packets
+            .iter()
+            .enumerate()
+            .fold((-1, -1), |(x, y), (i, packet)| {
+                if let Packet::Array(a) = packet
+                    && a.len() == 1
+                    && let Packet::Array(b) = a.first().unwrap()
+                    && b.len() == 1
+                    && let Packet::Int(value) = b.first().unwrap()
+                {
                     if *value == 2 {
-                        return (i as i32 + 1, y)
-                    } else if *value == 6  {
-                        return (x, i as i32 + 1)
+                        return (i as i32 + 1, y);
+                    } else if *value == 6 {
+                        return (x, i as i32 + 1);
                     }
                 }
-            }
-            (x, y)
-        });
+                (x, y)
+            });
 
-    div_x * div_y
+        if div_x < 0 || div_y < 0 {
+            return Err(AocError::solve(
+                "Unable to find divider packets [[2]] and [[6]]",
+            ));
+        }
+        Ok((div_x * div_y).into())
+    }
 }
 
 #[cfg(test)]
@@ -178,18 +173,6 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_day_13_1() {
-        let result = day_13_1("./src/files/day13_1.test");
-        assert_eq!(result, 13);
-    }
-
-    #[test]
-    fn test_day_13_2() {
-        let result = day_13_2("./src/files/day13_1.test");
-        assert_eq!(result, 140);
-    }
-
     #[test]
     fn test_packet_comparisons() {
         let a = Packet::Int(1);
@@ -231,16 +214,34 @@ mod tests {
 
     #[test]
     fn test_parse_line() {
-        let lines = vec![
-            "[1,2,3]".to_string(),
-            "[1,2,3,[4,5,[]]]".to_string(),
-            "[9]".to_string(),
-            "[[1],[2,3,4]]".to_string(),
-            "[[1],[2,3,14]]".to_string(),
+        let lines = [
+            "[1,2,3]",
+            "[1,2,3,[4,5,[]]]",
+            "[9]",
+            "[[1],[2,3,4]]",
+            "[[1],[2,3,14]]",
         ];
-        assert_debug_snapshot!(lines
-            .iter()
-            .map(|line| parse_line(line.to_string()))
-            .collect::<Vec<Vec<Packet>>>());
+        assert_debug_snapshot!(
+            lines
+                .iter()
+                .map(|line| parse_line(line).unwrap())
+                .collect::<Vec<Packet>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_line_error() {
+        assert_eq!(
+            parse_line("[1,[2]]]").unwrap_err().at_line(3).to_string(),
+            "<input>:3:8: Expected end of line"
+        );
+        assert_eq!(
+            parse_line("[1,a]").unwrap_err(),
+            AocError::parse(r#"Expected a number or "[""#).at_col(4)
+        );
+        assert_eq!(
+            parse_line("[[1],[2").unwrap_err(),
+            AocError::parse(r#"Expected "]""#).at_col(8)
+        );
     }
 }
@@ -1,4 +1,7 @@
-use crate::parser::{parse_aoc_file, parse_into_struct};
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{lines, parse_into_struct, read_aoc_file};
+use crate::solution::Puzzle;
 
 enum Shifumi {
     Rock,
@@ -27,7 +30,7 @@ fn convert_to_shifumi(letter: char) -> Shifumi {
         'A' | 'X' => Shifumi::Rock,
         'B' | 'Y' => Shifumi::Paper,
         'C' | 'Z' => Shifumi::Scissor,
-        _ => panic!("Invalid letter {letter}"),
+        _ => unreachable!("Letters are checked when parsing"),
     }
 }
 
@@ -43,65 +46,53 @@ fn get_shifumi_strategy(opp: &Shifumi, letter: char) -> Shifumi {
         (Shifumi::Scissor, 'X') => Shifumi::Paper,
         (Shifumi::Scissor, 'Y') => Shifumi::Scissor,
         (Shifumi::Scissor, 'Z') => Shifumi::Rock,
-        _ => panic!("Invalid letter {letter}"),
+        _ => unreachable!("Letters are checked when parsing"),
     }
 }
 
-pub fn day_2_1(filename: &str) -> i32 {
-    let games = parse_aoc_file(filename, None);
-    let scores = parse_into_struct(games, |l| {
-        let opp = l
-            .chars()
-            .next()
-            .unwrap_or_else(|| panic!("Unable to get 1st char at {l}"));
-        let you = l
-            .chars()
-            .nth(2)
-            .unwrap_or_else(|| panic!("Unable to get 2nd char at {l}"));
+pub struct Day2;
 
-        let opp_shifumi = convert_to_shifumi(opp);
-        let you_shifumi = convert_to_shifumi(you);
+impl Puzzle for Day2 {
+    // Opponent letter and second column letter of each round
+    type Input = Vec<(char, char)>;
 
-        resolve_shifumi(you_shifumi, opp_shifumi)
-    });
-
-    scores.iter().sum()
-}
-
-pub fn day_2_2(filename: &str) -> i32 {
-    let games = parse_aoc_file(filename, None);
-    let scores = parse_into_struct(games, |l| {
-        let opp = l
-            .chars()
-            .next()
-            .unwrap_or_else(|| panic!("Unable to get 1st char at {l}"));
-        let you = l
-            .chars()
-            .nth(2)
-            .unwrap_or_else(|| panic!("Unable to get 2nd char at {l}"));
-
-        let opp_shifumi = convert_to_shifumi(opp);
-        let you_shifumi = get_shifumi_strategy(&opp_shifumi, you);
-
-        resolve_shifumi(you_shifumi, opp_shifumi)
-    });
-
-    scores.iter().sum()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn parse(filename: &str) -> Result<Vec<(char, char)>> {
+        let input = read_aoc_file(filename)?;
+        parse_into_struct(lines(&input), |l| {
+            let opp = l.chars().next().ok_or_else(|| {
+                AocError::parse(format!("Unable to get 1st char at {l}")).at_col(1)
+            })?;
+            if !"ABC".contains(opp) {
+                return Err(AocError::parse(format!("Invalid letter {opp}")).at_col(1));
+            }
+            let you = l.chars().nth(2).ok_or_else(|| {
+                AocError::parse(format!("Unable to get 2nd char at {l}")).at_col(3)
+            })?;
+            if !"XYZ".contains(you) {
+                return Err(AocError::parse(format!("Invalid letter {you}")).at_col(3));
+            }
+            Ok((opp, you))
+        })
+    }
 
-    #[test]
-    fn test_day_2_1() {
-        let result = day_2_1("./src/files/day2_1.test");
-        assert_eq!(result, 15);
+    fn part1(games: &Vec<(char, char)>) -> Result<Answer> {
+        Ok(games
+            .iter()
+            .map(|(opp, you)| resolve_shifumi(convert_to_shifumi(*you), convert_to_shifumi(*opp)))
+            .sum::<i32>()
+            .into())
     }
 
-    #[test]
-    fn test_day_2_2() {
-        let result = day_2_2("./src/files/day2_2.test");
-        assert_eq!(result, 12);
+    fn part2(games: &Vec<(char, char)>) -> Result<Answer> {
+        Ok(games
+            .iter()
+            .map(|(opp, you)| {
+                let opp_shifumi = convert_to_shifumi(*opp);
+                let you_shifumi = get_shifumi_strategy(&opp_shifumi, *you);
+
+                resolve_shifumi(you_shifumi, opp_shifumi)
+            })
+            .sum::<i32>()
+            .into())
     }
 }
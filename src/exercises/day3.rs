@@ -1,63 +1,116 @@
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{lines, read_aoc_file};
+use crate::solution::Puzzle;
 
 fn char_to_value(c: &char) -> u32 {
-    if ('A'..='Z').contains(c) {
+    if c.is_ascii_uppercase() {
         return *c as u32 - 65 + 27;
     }
-    if ('a'..='z').contains(c) {
+    if c.is_ascii_lowercase() {
         return *c as u32 - 97 + 1;
     }
     0
 }
 
-pub fn day_3_1(filename: &str) -> u32 {
-    let rustsacks = parse_aoc_file(filename, None);
-    rustsacks
-        .iter()
-        .map(|line| {
-            let part1 = &line[0..line.len() / 2];
-            let part2 = &line[line.len() / 2..line.len()];
-
-            for c in part1.chars() {
-                if part2.contains(c) {
-                    return char_to_value(&c);
-                }
+// Each line is a rucksack of letters, its two compartments holding as many items
+fn parse_rucksacks(input: &str) -> Result<Vec<String>> {
+    (1..)
+        .zip(lines(input))
+        .map(|(number, line)| {
+            if let Some((col, item)) = (1..)
+                .zip(line.chars())
+                .find(|(_, c)| !c.is_ascii_alphabetic())
+            {
+                return Err(
+                    AocError::parse(format!("Expected an item letter, found {item:?}"))
+                        .at_line(number)
+                        .at_col(col),
+                );
             }
-
-            0
+            if line.len() % 2 != 0 {
+                return Err(AocError::parse(format!(
+                    "Expected an even number of items, found {}",
+                    line.len()
+                ))
+                .at_line(number)
+                .at_col(1));
+            }
+            Ok(line.to_string())
         })
-        .sum()
+        .collect()
 }
 
-pub fn day_3_2(filename: &str) -> u32 {
-    let rustsacks = parse_aoc_file(filename, None);
-    let (chunks, remain) = rustsacks.as_chunks::<3>();
-    if !remain.is_empty() {
-        panic!("Unable to split all the lines into chunks of 3");
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    // Rucksacks of ASCII letters, so they can be cut in two at any byte
+    type Input = Vec<String>;
+
+    fn parse(filename: &str) -> Result<Vec<String>> {
+        parse_rucksacks(&read_aoc_file(filename)?)
     }
 
-    chunks
-        .iter()
-        .map(|chunk| {
-            for c in chunk[0].chars() {
-                if chunk[1].contains(c) && chunk[2].contains(c) {
-                    return char_to_value(&c);
+    fn part1(rustsacks: &Vec<String>) -> Result<Answer> {
+        Ok(rustsacks
+            .iter()
+            .map(|line| {
+                let part1 = &line[0..line.len() / 2];
+                let part2 = &line[line.len() / 2..line.len()];
+
+                for c in part1.chars() {
+                    if part2.contains(c) {
+                        return char_to_value(&c);
+                    }
                 }
-            }
-            0
-        })
-        .sum()
+
+                0
+            })
+            .sum::<u32>()
+            .into())
+    }
+
+    fn part2(rustsacks: &Vec<String>) -> Result<Answer> {
+        let (chunks, remain) = rustsacks.as_chunks::<3>();
+        if !remain.is_empty() {
+            return Err(AocError::solve(
+                "Unable to split all the lines into chunks of 3",
+            ));
+        }
+
+        Ok(chunks
+            .iter()
+            .map(|chunk| {
+                for c in chunk[0].chars() {
+                    if chunk[1].contains(c) && chunk[2].contains(c) {
+                        return char_to_value(&c);
+                    }
+                }
+                0
+            })
+            .sum::<u32>()
+            .into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn test_day_3_1() {
-        assert_eq!(super::day_3_1("src/files/day3_1.test"), 157);
-    }
+    use super::*;
 
     #[test]
-    fn test_day_3_2() {
-        assert_eq!(super::day_3_2("src/files/day3_1.test"), 70);
+    fn test_parse_rucksacks() {
+        assert_eq!(parse_rucksacks("abCD\nvJrw").unwrap(), vec!["abCD", "vJrw"]);
+        assert_eq!(
+            parse_rucksacks("abCD\nvJéw").unwrap_err(),
+            AocError::parse("Expected an item letter, found 'é'")
+                .at_line(2)
+                .at_col(3)
+        );
+        assert_eq!(
+            parse_rucksacks("abC").unwrap_err(),
+            AocError::parse("Expected an even number of items, found 3")
+                .at_line(1)
+                .at_col(1)
+        );
     }
 }
@@ -1,97 +1,120 @@
-use crate::parser::{parse_aoc_file, parse_into_struct};
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy)]
-struct Interval {
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{lines, parse_into_struct, read_aoc_file};
+use crate::solution::Puzzle;
+
+/// A range of sections, bounds included, read from "min-max"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
     min: u32,
     max: u32,
 }
 
 impl Interval {
-    fn new(min: u32, max: u32) -> Self {
+    pub fn new(min: u32, max: u32) -> Self {
         Self { min, max }
     }
 
-    fn contains(&self, interval: &Interval) -> bool {
+    pub fn min(&self) -> u32 {
+        self.min
+    }
+
+    pub fn max(&self) -> u32 {
+        self.max
+    }
+
+    /// Whether the whole of the other interval is in this one
+    pub fn contains(&self, interval: &Interval) -> bool {
         self.min <= interval.min && self.max >= interval.max
     }
 
-    fn contains_value(&self, value: u32) -> bool {
+    pub fn contains_value(&self, value: u32) -> bool {
         self.min <= value && self.max >= value
     }
 
-    fn overlaps(&self, interval: &Interval) -> bool {
-        interval.contains_value(self.min) || interval.contains_value(self.max)
+    /// Whether the intervals have at least one section in common
+    pub fn overlaps(&self, interval: &Interval) -> bool {
+        self.min <= interval.max && interval.min <= self.max
     }
 }
 
-fn read_intervals(line: String) -> (Interval, Interval) {
-    let mut parts = line.split(',');
-    let part1 = parts
+// Reads a "min-max" interval, col being where it starts on the line
+fn read_interval(raw: &str, col: usize) -> Result<Interval> {
+    let mut bounds = raw.split('-');
+    let min = bounds
         .next()
-        .unwrap_or_else(|| panic!("Unable to get part1 from {line}"));
-    let part2 = parts
+        .ok_or_else(|| AocError::parse(format!("Unable to get min from {raw}")).at_col(col))?;
+    let max = bounds
         .next()
-        .unwrap_or_else(|| panic!("Unable to get part2 from {line}"));
-
-    let mut int1 = part1.split('-');
-    let interval1 = Interval::new(
-        int1.next()
-            .unwrap_or_else(|| panic!("Unable to get min from {part1}"))
-            .parse()
-            .unwrap_or_else(|_| panic!("Unable to parse min from {part1} (not a number)")),
-        int1.next()
-            .unwrap_or_else(|| panic!("Unable to get max from {part1}"))
-            .parse()
-            .unwrap_or_else(|_| panic!("Unable to parse max from {part1} (not a number)")),
-    );
-    let mut int2 = part2.split('-');
-    let interval2 = Interval::new(
-        int2.next()
-            .unwrap_or_else(|| panic!("Unable to get min from {part2}"))
-            .parse()
-            .unwrap_or_else(|_| panic!("Unable to parse min from {part2} (not a number)")),
-        int2.next()
-            .unwrap_or_else(|| panic!("Unable to get max from {part2}"))
-            .parse()
-            .unwrap_or_else(|_| panic!("Unable to parse max from {part2} (not a number)")),
-    );
-
-    (interval1, interval2)
+        .ok_or_else(|| AocError::parse(format!("Unable to get max from {raw}")).at_col(col))?;
+
+    Ok(Interval::new(
+        min.parse().map_err(|_| {
+            AocError::parse(format!("Unable to parse min from {raw} (not a number)")).at_col(col)
+        })?,
+        max.parse().map_err(|_| {
+            AocError::parse(format!("Unable to parse max from {raw} (not a number)"))
+                .at_col(col + min.len() + 1)
+        })?,
+    ))
 }
 
-pub fn day_4_1(filename: &str) -> u32 {
-    let lines = parse_aoc_file(filename, None);
-    let intervals: Vec<(Interval, Interval)> = parse_into_struct(lines, read_intervals);
-    intervals.iter().fold(0, |acc, cur| {
-        if cur.0.contains(&cur.1) || cur.1.contains(&cur.0) {
-            acc + 1
-        } else {
-            acc
-        }
-    })
+impl FromStr for Interval {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Interval> {
+        read_interval(s, 1)
+    }
 }
 
-pub fn day_4_2(filename: &str) -> u32 {
-    let lines = parse_aoc_file(filename, None);
-    let intervals: Vec<(Interval, Interval)> = parse_into_struct(lines, read_intervals);
-    intervals.iter().fold(0, |acc, cur| {
-        if cur.0.overlaps(&cur.1) || cur.1.overlaps(&cur.0) {
-            acc + 1
-        } else {
-            acc
-        }
-    })
+fn read_intervals(line: &str) -> Result<(Interval, Interval)> {
+    let Some((part1, part2)) = line.split_once(',') else {
+        return Err(
+            AocError::parse(format!("Unable to get part2 from {line}")).at_col(line.len() + 1)
+        );
+    };
+
+    Ok((
+        read_interval(part1, 1)?,
+        read_interval(part2, part1.len() + 2)?,
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test_day_4_1() {
-        assert_eq!(super::day_4_1("src/files/day4_1.test"), 2);
+pub struct Day4;
+
+impl Puzzle for Day4 {
+    type Input = Vec<(Interval, Interval)>;
+
+    fn parse(filename: &str) -> Result<Vec<(Interval, Interval)>> {
+        let input = read_aoc_file(filename)?;
+        parse_into_struct(lines(&input), read_intervals)
+    }
+
+    fn part1(intervals: &Vec<(Interval, Interval)>) -> Result<Answer> {
+        Ok(intervals
+            .iter()
+            .fold(0u32, |acc, cur| {
+                if cur.0.contains(&cur.1) || cur.1.contains(&cur.0) {
+                    acc + 1
+                } else {
+                    acc
+                }
+            })
+            .into())
     }
 
-    #[test]
-    fn test_day_4_2() {
-        assert_eq!(super::day_4_2("src/files/day4_1.test"), 4);
+    fn part2(intervals: &Vec<(Interval, Interval)>) -> Result<Answer> {
+        Ok(intervals
+            .iter()
+            .fold(0u32, |acc, cur| {
+                if cur.0.overlaps(&cur.1) || cur.1.overlaps(&cur.0) {
+                    acc + 1
+                } else {
+                    acc
+                }
+            })
+            .into())
     }
 }
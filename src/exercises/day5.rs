@@ -1,84 +1,78 @@
-use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::combinators::{
+    aligned, any_char, delimited, either, integer, literal, map, parse_complete, spaces,
+};
+use crate::parser::{AocParse, lines, parse_into_struct, parse_until_pattern, read_aoc_file};
+use crate::solution::Puzzle;
+
+// Index of a stack, stacks are numbered from 1 in the input
+#[derive(Debug, Clone, Copy)]
+struct Stack(usize);
+
+impl FromStr for Stack {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Stack, String> {
+        match s.parse::<usize>() {
+            Ok(0) => Err("stacks start at 1".to_string()),
+            Ok(n) => Ok(Stack(n - 1)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+// Shown as numbered in the input
+impl Display for Stack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 + 1)
+    }
+}
 
-use crate::parser::{parse_aoc_file, parse_until_pattern};
+impl Stack {
+    fn of(self, stacks: &mut [Vec<char>]) -> Result<&mut Vec<char>> {
+        stacks
+            .get_mut(self.0)
+            .ok_or_else(|| AocError::solve(format!("Unable to get stack {self}")))
+    }
+}
 
-#[derive(Debug)]
-struct Instruction {
+#[derive(Debug, AocParse)]
+#[aoc(regex = r"move (\d+) from (\d+) to (\d+)")]
+pub struct Instruction {
     number: usize,
-    from: usize,
-    to: usize,
+    from: Stack,
+    to: Stack,
 }
 
 impl Instruction {
-    fn from(instruction: String) -> Instruction {
-        let Ok(re) = Regex::new(r"move (\d*) from (\d*) to (\d*)") else {
-            panic!("Could not parse regex pattern at Instruction::from");
-        };
-        let matches = re
-            .captures(&instruction)
-            .unwrap_or_else(|| panic!("Could not parse instruction {instruction}"));
-
-        Instruction {
-            number: matches
-                .get(1)
-                .unwrap_or_else(|| panic!("Unable to read number from instruction {instruction}"))
-                .as_str()
-                .parse()
-                .unwrap_or_else(|e| {
-                    panic!("Unable to parse number from instruction {instruction} with error : {e}")
-                }),
-            from: matches
-                .get(2)
-                .unwrap_or_else(|| panic!("Unable to read 'from' from instruction {instruction}"))
-                .as_str()
-                .parse::<usize>()
-                .unwrap_or_else(|e| {
-                    panic!("Unable to parse 'from' from instruction {instruction} with error : {e}")
-                })
-                - 1,
-            to: matches
-                .get(3)
-                .unwrap_or_else(|| panic!("Unable to read 'to' from instruction {instruction}"))
-                .as_str()
-                .parse::<usize>()
-                .unwrap_or_else(|e| {
-                    panic!("Unable to parse 'to' from instruction {instruction} with error : {e}")
-                })
-                - 1,
-        }
-    }
-
-    fn execute(&self, stacks: &mut [Vec<char>]) {
+    fn execute(&self, stacks: &mut [Vec<char>]) -> Result<()> {
         let mut i = 0;
         loop {
-            let from = stacks
-                .get_mut(self.from)
-                .unwrap_or_else(|| panic!("Unable to get stack {}", self.from));
+            let from = self.from.of(stacks)?;
             if i == self.number || from.is_empty() {
                 break;
             }
 
             if let Some(item) = from.pop() {
-                stacks
-                    .get_mut(self.to)
-                    .unwrap_or_else(|| panic!("Unable to get stack {}", self.to))
-                    .push(item);
+                self.to.of(stacks)?.push(item);
             }
 
             i += 1;
         }
+        Ok(())
     }
 
-    fn execute_same_order(&self, stacks: &mut [Vec<char>]) {
+    fn execute_same_order(&self, stacks: &mut [Vec<char>]) -> Result<()> {
         // Moving one stack is the same as above function
         if self.number <= 1 {
-            self.execute(stacks);
-            return;
+            return self.execute(stacks);
         }
 
-        let from = stacks
-            .get_mut(self.from)
-            .unwrap_or_else(|| panic!("Unable to get stack {}", self.from));
+        let from = self.from.of(stacks)?;
         let temp: &mut Vec<char> = &mut vec![];
 
         for _ in 0..self.number {
@@ -89,32 +83,32 @@ impl Instruction {
 
         for _ in 0..self.number {
             if let Some(item) = temp.pop() {
-                stacks
-                    .get_mut(self.to)
-                    .unwrap_or_else(|| panic!("Unable to get stack {}", self.to))
-                    .push(item);
+                self.to.of(stacks)?.push(item);
             }
         }
+        Ok(())
     }
 }
 
-fn parse_stacks(filename: &str) -> (Vec<Vec<char>>, Vec<String>) {
-    let lines = parse_aoc_file(filename, None);
-    let (mut stack_lines, remaining) = parse_until_pattern(lines, "^$");
+// Reads the stacks drawing, returning the stacks and the lines after the drawing
+fn parse_stacks(lines: Vec<&str>) -> Result<(Vec<Vec<char>>, Vec<&str>)> {
+    let (mut stack_lines, remaining) = parse_until_pattern(lines, "^$")?;
+    // Line number of the last line of the drawing, which holds the stack numbers
+    let last_line = stack_lines.len();
     stack_lines.reverse();
 
     // Get first line
-    let (first_line, stack_lines) = stack_lines
-        .split_first()
-        .unwrap_or_else(|| panic!("Unable to get first line from {filename}"));
-
-    // Get the total number of stacks to compute
-    let num_stacks = first_line
-        .split_whitespace()
-        .map(|s| {
-            s.parse::<u32>()
-                .unwrap_or_else(|_| panic!("Unable to parse {s} on the first line (not a number)"))
-        })
+    let (first_line, stack_lines) = stack_lines.split_first().ok_or_else(|| {
+        AocError::parse("Unable to get first line")
+            .at_line(1)
+            .at_col(1)
+    })?;
+
+    // Get the total number of stacks to compute, numbers are centered in 3 wide columns
+    let numbers = aligned(3, 1, delimited(spaces, integer::<usize>, spaces));
+    let num_stacks = parse_complete(first_line, numbers)
+        .map_err(|e| e.at_line(last_line))?
+        .into_iter()
         .max()
         .unwrap_or(0);
 
@@ -124,94 +118,132 @@ fn parse_stacks(filename: &str) -> (Vec<Vec<char>>, Vec<String>) {
         stacks.push(Vec::new());
     }
 
-    // Fill the stacks
-    for line in stack_lines {
-        let mut chars = line.chars();
-        let i = 1;
-        for j in 0..num_stacks {
-            let char_index = (i + 4u32 * j) as usize;
-            let char = chars.nth(if j == 0 { 1 } else { 3 }).unwrap_or_else(|| {
-                panic!("Unable to get char at index {char_index} on line {line}")
-            });
-            if char != ' ' {
-                stacks
-                    .get_mut(j as usize)
-                    .unwrap_or_else(|| panic!("Stack {j} does not exists"))
-                    .push(char);
+    // Fill the stacks, each column is either a crate like [A] or blank
+    let crate_cell = either(
+        map(delimited(literal("["), any_char, literal("]")), Some),
+        map(spaces, |_| None),
+    );
+    let crates = aligned(3, 1, crate_cell);
+    for (k, line) in stack_lines.iter().enumerate() {
+        let line_number = last_line - 1 - k;
+        let row = parse_complete(line, &crates).map_err(|e| e.at_line(line_number))?;
+        if row.len() > num_stacks {
+            return Err(
+                AocError::parse(format!("Crate outside of the {num_stacks} stacks"))
+                    .at_line(line_number)
+                    .at_col(4 * num_stacks + 1),
+            );
+        }
+        for (stack, item) in stacks.iter_mut().zip(row) {
+            if let Some(item) = item {
+                stack.push(item);
             }
         }
     }
 
-    (stacks, remaining[1..].to_vec())
+    if remaining.is_empty() {
+        return Err(AocError::parse("Missing blank line after the stacks")
+            .at_line(last_line)
+            .at_col(first_line.len() + 1));
+    }
+
+    Ok((stacks, remaining[1..].to_vec()))
 }
 
-pub fn day_5_1(filename: &str) -> Vec<char> {
-    let (mut stacks, instructions) = parse_stacks(filename);
+// Top crate of every non empty stack
+fn top_crates(stacks: &[Vec<char>]) -> String {
+    stacks.iter().filter_map(|s| s.last()).collect()
+}
 
-    for instruction in instructions {
-        Instruction::from(instruction).execute(&mut stacks);
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    type Input = (Vec<Vec<char>>, Vec<Instruction>);
+
+    fn parse(filename: &str) -> Result<(Vec<Vec<char>>, Vec<Instruction>)> {
+        let input = read_aoc_file(filename)?;
+        let lines: Vec<&str> = lines(&input).collect();
+        let total = lines.len();
+        let (stacks, instructions) = parse_stacks(lines)?;
+        // Instructions are at the end of the file
+        let offset = total - instructions.len();
+        let instructions = parse_into_struct(instructions, Instruction::aoc_parse)
+            .map_err(|e| e.offset_lines(offset))?;
+        Ok((stacks, instructions))
     }
 
-    stacks
-        .iter()
-        .map(|s| s.last().unwrap_or(&' '))
-        .filter(|c| **c != ' ')
-        .cloned()
-        .collect()
-}
-
-pub fn day_5_2(filename: &str) -> Vec<char> {
-    let (mut stacks, instructions) = parse_stacks(filename);
+    fn part1((stacks, instructions): &(Vec<Vec<char>>, Vec<Instruction>)) -> Result<Answer> {
+        let mut stacks = stacks.clone();
+        for instruction in instructions {
+            instruction.execute(&mut stacks)?;
+        }
 
-    for instruction in instructions {
-        Instruction::from(instruction).execute_same_order(&mut stacks);
+        Ok(top_crates(&stacks).into())
     }
 
-    stacks
-        .iter()
-        .map(|s| s.last().unwrap_or(&' '))
-        .filter(|c| **c != ' ')
-        .cloned()
-        .collect()
+    fn part2((stacks, instructions): &(Vec<Vec<char>>, Vec<Instruction>)) -> Result<Answer> {
+        let mut stacks = stacks.clone();
+        for instruction in instructions {
+            instruction.execute_same_order(&mut stacks)?;
+        }
+
+        Ok(top_crates(&stacks).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
 
-    use super::{parse_stacks, Instruction};
+    use super::{Instruction, parse_stacks};
+    use crate::error::AocError;
+    use crate::parser::{AocParse, lines, read_aoc_file};
 
     #[test]
     fn test_parse_stacks() {
-        let parsed = parse_stacks("src/files/day5_1.test");
+        let input = read_aoc_file("src/examples/day5.txt").unwrap();
+        let parsed = parse_stacks(lines(&input).collect()).unwrap();
         assert_debug_snapshot!(parsed);
     }
 
     #[test]
     fn test_instruction_parsing() {
-        let raw = "move 1 from 1 to 2".to_string();
-        let instruction = super::Instruction::from(raw);
+        let raw = "move 1 from 1 to 2";
+        let instruction = super::Instruction::aoc_parse(raw).unwrap();
         assert_debug_snapshot!(instruction);
     }
 
+    #[test]
+    fn test_instruction_parsing_error() {
+        let error = Instruction::aoc_parse("move 1 from 0 to 2").unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse("Invalid from `0`: stacks start at 1")
+                .at_line(1)
+                .at_col(13)
+        );
+
+        let error = Instruction::aoc_parse("move x from 1 to 2").unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse(r#"Expected Instruction matching "move (\\d+) from (\\d+) to (\\d+)""#)
+                .at_line(1)
+                .at_col(1)
+        );
+    }
+
     #[test]
     fn test_instruction_execute() {
         let mut stacks = vec![vec!['a'], vec![]];
-        let instruction = Instruction::from("move 1 from 1 to 2".to_string());
-        instruction.execute(&mut stacks);
+        let instruction = Instruction::aoc_parse("move 1 from 1 to 2").unwrap();
+        instruction.execute(&mut stacks).unwrap();
 
         assert_debug_snapshot!(stacks);
-    }
 
-    #[test]
-    fn test_day_5_1() {
-        let result = super::day_5_1("src/files/day5_1.test");
-        assert_debug_snapshot!(result);
-    }
-
-    #[test]
-    fn test_day_5_2() {
-        let result = super::day_5_2("src/files/day5_1.test");
-        assert_debug_snapshot!(result);
+        let instruction = Instruction::aoc_parse("move 1 from 2 to 3").unwrap();
+        assert_eq!(
+            instruction.execute(&mut stacks).unwrap_err(),
+            AocError::solve("Unable to get stack 3")
+        );
     }
 }
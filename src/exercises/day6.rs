@@ -1,13 +1,11 @@
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{lines, read_aoc_file};
+use crate::solution::Puzzle;
 
 // There should be something easier
 fn has_multiple_char_occ(input: &str) -> bool {
-    let mut chars = input.chars();
-    loop {
-        let Some(c) = chars.next() else {
-            break;
-        };
-
+    for c in input.chars() {
         if input.matches(c).count() != 1 {
             return true;
         }
@@ -15,7 +13,7 @@ fn has_multiple_char_occ(input: &str) -> bool {
     false
 }
 
-fn make_substrings(input: &String, size: Option<usize>) -> Vec<String> {
+fn make_substrings(input: &str, size: Option<usize>) -> Vec<String> {
     let n = size.unwrap_or(4);
     let mut result: Vec<String> = Vec::new();
 
@@ -32,52 +30,45 @@ fn make_substrings(input: &String, size: Option<usize>) -> Vec<String> {
     result
 }
 
-pub fn day_6_1(filename: &str) -> Vec<u32> {
-    let file = parse_aoc_file(filename, None);
-    file.iter()
-        .map(|line| {
-            let substrings = make_substrings(line, None);
-            let mut i: u32 = 0;
-            loop {
-                let Some(substring) = substrings.get(i as usize) else {
-                break;
-            };
-                if !has_multiple_char_occ(substring) {
-                    break;
-                }
-                i += 1;
-            }
-            i + 4
-        })
-        .collect()
+// Number of characters read before the end of the first marker of the given size
+fn find_marker(line: &str, size: usize) -> u32 {
+    let substrings = make_substrings(line, Some(size));
+    let mut i: u32 = 0;
+    while let Some(substring) = substrings.get(i as usize) {
+        if !has_multiple_char_occ(substring) {
+            break;
+        }
+        i += 1;
+    }
+    i + size as u32
 }
 
-pub fn day_6_2(filename: &str) -> Vec<u32> {
-    let file = parse_aoc_file(filename, None);
-    file.iter()
-        .map(|line| {
-            let substrings = make_substrings(line, Some(14));
-            let mut i: u32 = 0;
-            loop {
-                let Some(substring) = substrings.get(i as usize) else {
-                break;
-            };
-                if !has_multiple_char_occ(substring) {
-                    i += 0;
-                    break;
-                }
-                i += 1;
-            }
-            i + 14
-        })
-        .collect()
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    // The datastream, on the first line
+    type Input = String;
+
+    fn parse(filename: &str) -> Result<String> {
+        let input = read_aoc_file(filename)?;
+        match lines(&input).next() {
+            Some(datastream) if !datastream.is_empty() => Ok(datastream.to_string()),
+            _ => Err(AocError::parse("No datastream found").at_line(1).at_col(1)),
+        }
+    }
+
+    fn part1(datastream: &String) -> Result<Answer> {
+        Ok(find_marker(datastream, 4).into())
+    }
+
+    fn part2(datastream: &String) -> Result<Answer> {
+        Ok(find_marker(datastream, 14).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::exercises::day6::{day_6_2, has_multiple_char_occ};
-
-    use super::day_6_1;
+    use super::*;
 
     #[test]
     fn test_make_substrings() {
@@ -92,16 +83,4 @@ mod tests {
         let result: Vec<bool> = input.iter().map(|s| has_multiple_char_occ(s)).collect();
         assert_eq!(result, vec![false, true]);
     }
-
-    #[test]
-    fn test_day_6_1() {
-        let result: Vec<u32> = day_6_1("src/files/day6_1.test");
-        assert_eq!(result, vec![5, 6, 10, 12]);
-    }
-
-    #[test]
-    fn test_day_6_2() {
-        let result: Vec<u32> = day_6_2("src/files/day6_2.test");
-        assert_eq!(result, vec![19, 23, 23, 29, 27]);
-    }
 }
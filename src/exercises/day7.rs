@@ -1,4 +1,7 @@
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::{lines, read_aoc_file};
+use crate::solution::Puzzle;
 
 enum Cmd {
     Cd(String),
@@ -6,7 +9,7 @@ enum Cmd {
 }
 
 #[derive(Debug)]
-struct Directory {
+pub struct Directory {
     path: Vec<String>,
     file_size: u32,
     sub_dirs: Vec<Vec<String>>,
@@ -27,19 +30,13 @@ impl Directory {
 fn parse_command_line(line: &str) -> Option<Cmd> {
     let mut splits = line.split(' ').collect::<Vec<&str>>();
     splits.reverse();
-    let Some(first) = splits.pop() else {
-        return None;
-    };
+    let first = splits.pop()?;
     if first != "$" {
         return None;
     }
-    let Some(second) = splits.pop() else {
-        return None;
-    };
+    let second = splits.pop()?;
     if second == "cd" {
-        let Some(third) = splits.pop() else {
-            return None;
-        };
+        let third = splits.pop()?;
         return Some(Cmd::Cd(third.to_string()));
     }
     if second == "ls" {
@@ -51,57 +48,51 @@ fn parse_command_line(line: &str) -> Option<Cmd> {
 // parses a result line, which is always after a ls command
 // if it is a dir, put it in the sub_dirs of the current directory
 // otherwise is is a file, so add its size to the current directory
-fn parse_ls_line(line: &str, directory: &mut Directory, pwd: &[String]) {
+fn parse_ls_line(line: &str, directory: &mut Directory, pwd: &[String]) -> Result<()> {
     let mut splits = line.split(' ').collect::<Vec<&str>>();
     splits.reverse();
     let Some(first) = splits.pop() else {
-        return;
+        return Ok(());
     };
     if first == "dir" {
         let Some(second) = splits.pop() else {
-            return;
+            return Ok(());
         };
         let mut new_dir = pwd.to_owned();
         new_dir.push(second.to_string());
         directory.sub_dirs.push(new_dir);
-        return;
+        return Ok(());
     }
     directory.file_size += first
         .parse::<u32>()
-        .unwrap_or_else(|_| panic!("Could not parse {first} as u32"));
+        .map_err(|_| AocError::parse(format!("Could not parse {first} as u32")).at_col(1))?;
+    Ok(())
 }
 
 // dirty recursive function that i could have derecursified but it was ok performance wise
-fn compute_dir_size(directory: &Directory, all: &Vec<Directory>) -> u32 {
-    directory.file_size
-        + directory
-            .sub_dirs
-            .iter()
-            .map(|s| {
-                let Some(dir) = all.iter().find(|d| d.path == *s) else {
-            panic!("Could not find directory {s:?}");
+fn compute_dir_size(directory: &Directory, all: &Vec<Directory>) -> Result<u32> {
+    let mut size = directory.file_size;
+    for s in &directory.sub_dirs {
+        let Some(dir) = all.iter().find(|d| d.path == *s) else {
+            return Err(AocError::solve(format!("Could not find directory {s:?}")));
         };
-                compute_dir_size(dir, all)
-            })
-            .sum::<u32>()
+        size += compute_dir_size(dir, all)?;
+    }
+    Ok(size)
 }
 
 // compute all directories, their size without subdirectories and their subdirectories
-fn compute_directories(filename: &str) -> Vec<Directory> {
-    let mut lines = parse_aoc_file(filename, None);
-    lines.reverse();
+fn compute_directories(filename: &str) -> Result<Vec<Directory>> {
+    let input = read_aoc_file(filename)?;
 
     let mut directories: Vec<Directory> = Vec::new();
     let mut pwd: Vec<String> = Vec::new();
     let mut current_dir: Directory = Directory::new(Vec::new());
 
     // Fill directories first, without calculating additional file sizes
-    loop {
-        let Some(line) = lines.pop() else {
-            break;
-        };
-        let Some(command) = parse_command_line(&line) else {
-            parse_ls_line(&line, &mut current_dir, &pwd);
+    for (i, line) in lines(&input).enumerate() {
+        let Some(command) = parse_command_line(line) else {
+            parse_ls_line(line, &mut current_dir, &pwd).map_err(|e| e.at_line(i + 1))?;
             continue;
         };
 
@@ -125,57 +116,46 @@ fn compute_directories(filename: &str) -> Vec<Directory> {
     // Push last current_dir
     directories.push(current_dir);
 
-    directories
-}
-
-pub fn day_7_1(filename: &str) -> u32 {
-    let directories = compute_directories(filename);
-
-    directories.iter().fold(0, |acc, d| {
-        let size = compute_dir_size(d, &directories);
-        if size <= 100000 {
-            acc + size
-        } else {
-            acc
-        }
-    })
+    Ok(directories)
 }
 
-pub fn day_7_2(filename: &str) -> u32 {
-    let directories = compute_directories(filename);
-
-    // get 2nd directory, which is the computed root, above algorithm inserts an empty dir at first position.
-    let Some(first_dir) = directories.get(1) else {
-        panic!("Could not find root directory");
-    };
-    let root_size = compute_dir_size(first_dir, &directories);
-    let unused = 70000000 - root_size;
-    let to_free = 30000000 - unused;
-    let max = root_size;
-
-    directories.iter().fold(max, |acc, d| {
-        let size = compute_dir_size(d, &directories);
-        if size > to_free && size <= acc {
-            size
-        } else {
-            acc
-        }
-    })
-}
+pub struct Day7;
 
-#[cfg(test)]
-mod tests {
-    use crate::exercises::day7::day_7_2;
+impl Puzzle for Day7 {
+    type Input = Vec<Directory>;
 
-    use super::day_7_1;
+    fn parse(filename: &str) -> Result<Vec<Directory>> {
+        compute_directories(filename)
+    }
 
-    #[test]
-    fn test_day_7_1() {
-        assert_eq!(day_7_1("src/files/day7_1.test"), 95437);
+    fn part1(directories: &Vec<Directory>) -> Result<Answer> {
+        let total = directories.iter().try_fold(0u32, |acc, d| {
+            let size = compute_dir_size(d, directories)?;
+            Ok::<u32, AocError>(if size <= 100000 { acc + size } else { acc })
+        })?;
+        Ok(total.into())
     }
 
-    #[test]
-    fn test_day_7_2() {
-        assert_eq!(day_7_2("src/files/day7_1.test"), 24933642);
+    fn part2(directories: &Vec<Directory>) -> Result<Answer> {
+        // get 2nd directory, which is the computed root, above algorithm inserts an empty dir at first position.
+        let Some(first_dir) = directories.get(1) else {
+            return Err(AocError::solve("Could not find root directory"));
+        };
+        let root_size = compute_dir_size(first_dir, directories)?;
+        let unused = 70000000u32.checked_sub(root_size).ok_or_else(|| {
+            AocError::solve(format!("Root size {root_size} is larger than the disk"))
+        })?;
+        let to_free = 30000000u32.saturating_sub(unused);
+        let max = root_size;
+
+        let smallest = directories.iter().try_fold(max, |acc, d| {
+            let size = compute_dir_size(d, directories)?;
+            Ok::<u32, AocError>(if size > to_free && size <= acc {
+                size
+            } else {
+                acc
+            })
+        })?;
+        Ok(smallest.into())
     }
 }
@@ -1,275 +1,89 @@
-use std::cmp::{max, min};
+use std::collections::HashSet;
 
-use crate::parser::parse_aoc_file;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::geometry::{Direction, Point};
+use crate::parser::{lines, parse_into_struct_with, read_aoc_file};
+use crate::solution::{Params, Puzzle};
 
-type Position = (i32, i32);
-
-enum Move {
-    L(u32),
-    R(u32),
-    U(u32),
-    D(u32),
-}
-
-fn get_moves(filename: &str) -> Vec<Move> {
-    let lines = parse_aoc_file(filename, None);
-    lines
-        .iter()
-        .map(|l| {
-            let splits = l.split(' ').collect::<Vec<&str>>();
-            let first = splits
-                .first()
-                .unwrap_or_else(|| panic!("Unable to get direction from {l}"));
-            let second = splits
-                .last()
-                .unwrap_or_else(|| panic!("Unable to get value from {l}"));
-            let val = second
-                .parse::<u32>()
-                .unwrap_or_else(|_| panic!("Unable to parse {second}"));
-            match *first {
-                "L" => Move::L(val),
-                "R" => Move::R(val),
-                "U" => Move::U(val),
-                "D" => Move::D(val),
-                _ => panic!("Unknown direction"),
-            }
-        })
-        .collect()
+pub struct Move {
+    direction: Direction,
+    steps: u32,
 }
 
-fn distance(p1: &Position, p2: &Position) -> f32 {
-    let dx = (p1.0 - p2.0).abs();
-    let dy = (p1.1 - p2.1).abs();
-    let min = min(dx, dy);
-    let max = max(dx, dy);
-    let straight_path = max - min;
-
-    f32::sqrt(2f32) * min as f32 + straight_path as f32
+fn get_moves(filename: &str) -> Result<Vec<Move>> {
+    let input = read_aoc_file(filename)?;
+    parse_into_struct_with(lines(&input), |l| {
+        let Some((first, second)) = l.split_once(' ') else {
+            return Err(
+                AocError::parse(format!("Unable to get direction and value from {l}")).at_col(1),
+            );
+        };
+        let direction = first.parse::<Direction>().map_err(|e| e.at_col(1))?;
+        let steps = second.parse::<u32>().map_err(|_| {
+            AocError::parse(format!("Unable to parse {second}")).at_col(first.len() + 2)
+        })?;
+        Ok(Move { direction, steps })
+    })
 }
 
-fn recompute_rope_tail(head: &Position, tail: &mut Position) {
-    if distance(head, tail) <= 1f32 {
-        return;
-    }
-
-    let dx = (tail.0 - head.0).abs();
-    let dy = (tail.1 - head.1).abs();
-
-    if dx > dy {
-        if tail.0 < head.0 {
-            tail.0 = head.0 - 1;
-        }
-        if tail.0 > head.0 {
-            tail.0 = head.0 + 1;
-        }
-        tail.1 = head.1;
-    }
-    if dx < dy {
-        if tail.1 < head.1 {
-            tail.1 = head.1 - 1;
-        }
-        if tail.1 > head.1 {
-            tail.1 = head.1 + 1;
-        }
-        tail.0 = head.0;
+// A knot that no longer touches the previous one moves one step towards it, diagonally if needed
+fn follow(knot: &mut Point, previous: Point) {
+    if !knot.is_adjacent(previous) {
+        *knot += (previous - *knot).signum();
     }
 }
 
-fn recompute_rope(
-    head: &mut Position,
-    tail: &mut Position,
-    all_positions: &mut Vec<Position>,
-    movement: Move,
-) {
-    match movement {
-        Move::L(v) => {
-            for _ in 0..v {
-                head.0 -= 1;
-                recompute_rope_tail(head, tail);
-                all_positions.push(*tail);
-            }
-        }
-        Move::R(v) => {
-            for _ in 0..v {
-                head.0 += 1;
-                recompute_rope_tail(head, tail);
-                all_positions.push(*tail);
-            }
-        }
-        Move::U(v) => {
-            for _ in 0..v {
-                head.1 -= 1;
-                recompute_rope_tail(head, tail);
-                all_positions.push(*tail);
-            }
-        }
-        Move::D(v) => {
-            for _ in 0..v {
-                head.1 += 1;
-                recompute_rope_tail(head, tail);
-                all_positions.push(*tail);
-            }
-        }
-    }
-}
-
-fn recompute_rope_tail_multiple(mut rope: Vec<Position>, index: usize) -> Vec<Position> {
-    let mut positions = Vec::new();
-    let mut dx = (rope[index + 1].0 - rope[index].0).abs();
-    let mut dy = (rope[index + 1].1 - rope[index].1).abs();
+// Number of distinct positions visited by the last knot of a rope of the given size
+fn count_tail_positions(moves: &[Move], rope_size: usize) -> usize {
+    let mut rope = vec![Point::ORIGIN; rope_size.max(1)];
+    let mut positions = HashSet::from([Point::ORIGIN]);
 
-    while distance(&rope[index], &rope[index + 1]) > f32::sqrt(2f32) {
-        if dx > 0 {
-            if rope[index + 1].0 < rope[index].0 {
-                rope[index + 1].0 += 1;
-            }
-            if rope[index + 1].0 > rope[index].0 {
-                rope[index + 1].0 -= 1;
-            }
-        }
-
-        if dy > 0 {
-            if rope[index + 1].1 < rope[index].1 {
-                rope[index + 1].1 += 1;
-            }
-            if rope[index + 1].1 > rope[index].1 {
-                rope[index + 1].1 -= 1;
+    for movement in moves {
+        for _ in 0..movement.steps {
+            rope[0] += movement.direction;
+            for i in 1..rope.len() {
+                let previous = rope[i - 1];
+                follow(&mut rope[i], previous);
             }
+            positions.extend(rope.last());
         }
-
-        dx = (rope[index + 1].0 - rope[index].0).abs();
-        dy = (rope[index + 1].1 - rope[index].1).abs();
-
-        positions.push(rope[index + 1]);
     }
 
-    positions
-}
-
-fn recompute_rope_multiple(
-    rope: &mut Vec<Position>,
-    all_positions: &mut Vec<Position>,
-    movement: &Move,
-) {
-    match movement {
-        Move::L(v) => {
-            for _ in 0..*v {
-                rope[0].0 -= 1;
-                for i in 0..rope.len() - 1 {
-                    let positions = recompute_rope_tail_multiple(rope.clone(), i);
-                    if let Some(p) = positions.last() {
-                        rope[i + 1] = *p;
-                    }
-                    if i == rope.len() - 2 {
-                        for v in positions {
-                            all_positions.push(v);
-                        }
-                    }
-                }
-            }
-        }
-        Move::R(v) => {
-            for _ in 0..*v {
-                rope[0].0 += 1;
-                for i in 0..rope.len() - 1 {
-                    let positions = recompute_rope_tail_multiple(rope.clone(), i);
-                    if let Some(p) = positions.last() {
-                        rope[i + 1] = *p;
-                    }
-                    if i == rope.len() - 2 {
-                        for v in positions {
-                            all_positions.push(v);
-                        }
-                    }
-                }
-            }
-        }
-        Move::U(v) => {
-            for _ in 0..*v {
-                rope[0].1 -= 1;
-                for i in 0..rope.len() - 1 {
-                    let positions = recompute_rope_tail_multiple(rope.clone(), i);
-                    if let Some(p) = positions.last() {
-                        rope[i + 1] = *p;
-                    }
-                    if i == rope.len() - 2 {
-                        for v in positions {
-                            all_positions.push(v);
-                        }
-                    }
-                }
-            }
-        }
-        Move::D(v) => {
-            for _ in 0..*v {
-                rope[0].1 += 1;
-                for i in 0..rope.len() - 1 {
-                    let positions = recompute_rope_tail_multiple(rope.clone(), i);
-                    if let Some(p) = positions.last() {
-                        rope[i + 1] = *p;
-                    }
-                    if i == rope.len() - 2 {
-                        for v in positions {
-                            all_positions.push(v);
-                        }
-                    }
-                }
-            }
-        }
-    }
+    positions.len()
 }
 
-pub fn day_9_1(filename: &str) -> u32 {
-    let moves = get_moves(filename);
-    let mut positions: Vec<Position> = Vec::new();
-    let mut head = (0, 0);
-    let mut tail = (0, 0);
+pub struct Day9;
 
-    positions.push(tail);
+impl Puzzle for Day9 {
+    type Input = Vec<Move>;
 
-    for movement in moves {
-        recompute_rope(&mut head, &mut tail, &mut positions, movement);
-    }
-
-    positions.sort();
-    positions.dedup();
-
-    positions.len() as u32
-}
-
-pub fn day_9_2(filename: &str, rope_size: u32) -> u32 {
-    let moves = get_moves(filename);
-    let mut positions: Vec<Position> = Vec::new();
-    let mut rope: Vec<Position> = Vec::new();
-
-    for _ in 0..rope_size {
-        rope.push((0, 0));
+    fn parse(filename: &str) -> Result<Vec<Move>> {
+        get_moves(filename)
     }
 
-    positions.push((0, 0));
-
-    for movement in moves {
-        recompute_rope_multiple(&mut rope, &mut positions, &movement);
+    fn part1(moves: &Vec<Move>) -> Result<Answer> {
+        count_tail_positions(moves, 2).try_into()
     }
 
-    positions.sort();
-    positions.dedup();
-
-    positions.len() as u32
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_day_9_1() {
-        assert_eq!(day_9_1("src/files/day9_1.test"), 13);
+    fn part2(moves: &Vec<Move>) -> Result<Answer> {
+        count_tail_positions(moves, 10).try_into()
     }
 
-    #[test]
-    fn test_day_9_2() {
-        assert_eq!(day_9_2("src/files/day9_1.test", 2), 13);
-        assert_eq!(day_9_2("src/files/day9_2.test", 10), 36);
+    // The `rope` parameter replaces the number of knots of the part
+    fn solve(moves: &Vec<Move>, part: u8, params: &Params) -> Result<Answer> {
+        if let Some(name) = params.keys().find(|name| *name != "rope") {
+            return Err(AocError::solve(format!("Unknown parameter {name}")));
+        }
+        let Some(rope) = params.get("rope") else {
+            return match part {
+                1 => Self::part1(moves),
+                2 => Self::part2(moves),
+                _ => Err(AocError::solve(format!("Puzzles have no part {part}"))),
+            };
+        };
+        let rope = usize::try_from(*rope)
+            .map_err(|_| AocError::solve(format!("Invalid rope length {rope}")))?;
+        count_tail_positions(moves, rope).try_into()
     }
 }
@@ -1,3 +1,7 @@
+//! One module per day, each with its puzzle type such as day4::Day4 and the types of its input
+
+use crate::solution::Solution;
+
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -11,3 +15,23 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+/// Every solved day, in order
+/// Adding a day only means adding its module above and registering it here
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new::<day1::Day1>(1),
+        Solution::new::<day2::Day2>(2),
+        Solution::new::<day3::Day3>(3),
+        Solution::new::<day4::Day4>(4),
+        Solution::new::<day5::Day5>(5),
+        Solution::new::<day6::Day6>(6),
+        Solution::new::<day7::Day7>(7),
+        Solution::new::<day8::Day8>(8),
+        Solution::new::<day9::Day9>(9),
+        Solution::new::<day10::Day10>(10),
+        Solution::new::<day11::Day11>(11),
+        Solution::new::<day12::Day12>(12),
+        Solution::new::<day13::Day13>(13),
+    ]
+}
@@ -0,0 +1,229 @@
+//! Integer points and directions on a plane where y grows downwards, as in the puzzle inputs
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::error::AocError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// Number of steps between two points when moving only up, down, left and right
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Number of steps between two points when diagonal moves are allowed
+    pub fn chebyshev(self, other: Point) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Each coordinate reduced to -1, 0 or 1: one step from the origin towards the point
+    pub fn signum(self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Whether the points are the same or touch, diagonals included
+    pub fn is_adjacent(self, other: Point) -> bool {
+        self.chebyshev(other) <= 1
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self + direction.step()
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = *self + direction;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Up, right, down and left, turning clockwise
+    pub const ALL4: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Every direction including diagonals, turning clockwise from up
+    pub const ALL8: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// The move of one step in this direction
+    pub fn step(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::UpRight => Point::new(1, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::DownRight => Point::new(1, 1),
+            Direction::Down => Point::new(0, 1),
+            Direction::DownLeft => Point::new(-1, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right().turn_right().turn_right()
+    }
+
+    /// An eighth of a turn clockwise
+    pub fn turn_right(self) -> Direction {
+        let i = Direction::ALL8.iter().position(|d| *d == self).unwrap_or(0);
+        Direction::ALL8[(i + 1) % 8]
+    }
+
+    /// An eighth of a turn counterclockwise
+    pub fn turn_left(self) -> Direction {
+        let i = Direction::ALL8.iter().position(|d| *d == self).unwrap_or(0);
+        Direction::ALL8[(i + 7) % 8]
+    }
+}
+
+// Reads U, D, L or R as used in the puzzle inputs
+impl FromStr for Direction {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Direction, AocError> {
+        match s {
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            _ => Err(AocError::parse(format!(
+                "Unknown direction `{s}`, expected U, D, L or R"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, -2);
+        let b = Point::new(4, 2);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!((b - a).signum(), Point::new(1, 1));
+        assert_eq!((a - a).signum(), Point::ORIGIN);
+        assert!(a.is_adjacent(a + Direction::DownLeft));
+        assert!(!a.is_adjacent(a + Direction::Up.step() * 2));
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!("U".parse(), Ok(Direction::Up));
+        assert_eq!("R".parse(), Ok(Direction::Right));
+        assert_eq!(
+            "X".parse::<Direction>(),
+            Err(AocError::parse(
+                "Unknown direction `X`, expected U, D, L or R"
+            ))
+        );
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+        assert_eq!(Direction::Left.turn_right(), Direction::UpLeft);
+        assert_eq!(Direction::Up.turn_left(), Direction::UpLeft);
+        assert!(
+            Direction::ALL8
+                .iter()
+                .all(|d| Point::ORIGIN + *d + d.opposite() == Point::ORIGIN)
+        );
+    }
+}
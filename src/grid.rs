@@ -0,0 +1,324 @@
+//! Helpers shared by the grid puzzles, not every day needs all of them
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use crate::error::{AocError, Result};
+use crate::geometry::{Direction, Point};
+use crate::parser::parse_into_struct_with;
+
+/// A rectangle of cells, stored row after row in a single vector
+/// Cells are addressed by points, x being the column and y the row, from (0, 0) on the top left
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Reads one cell per character, errors are located on the line and column of the cell
+    /// Every line must have as many cells as the first one
+    pub fn parse<E>(
+        input: &str,
+        mut cell: impl FnMut(char) -> std::result::Result<T, E>,
+    ) -> Result<Grid<T>>
+    where
+        E: Into<AocError>,
+    {
+        let width = input.lines().next().map_or(0, |l| l.chars().count());
+        let rows = parse_into_struct_with(input.lines(), |line| {
+            let row = (1..)
+                .zip(line.chars())
+                .map(|(col, c)| cell(c).map_err(|e| e.into().at_col(col)))
+                .collect::<Result<Vec<T>>>()?;
+            if row.len() != width {
+                return Err(AocError::parse(format!(
+                    "Expected {width} cells, found {}",
+                    row.len()
+                ))
+                .at_col(row.len().min(width) + 1));
+            }
+            Ok(row)
+        })?;
+        Grid::from_rows(rows)
+    }
+
+    /// Builds a grid from its rows, which must all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        if let Some(i) = rows.iter().position(|row| row.len() != width) {
+            return Err(AocError::parse(format!(
+                "Expected rows of {width} cells, found {}",
+                rows[i].len()
+            ))
+            .at_line(i + 1));
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Index of a point in the cells, if it is in the grid
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let x = usize::try_from(point.x).ok().filter(|x| *x < self.width)?;
+        let y = usize::try_from(point.y).ok().filter(|y| *y < self.height)?;
+        Some(y * self.width + x)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(self.index_of(point)?)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        let i = self.index_of(point)?;
+        self.cells.get_mut(i)
+    }
+
+    /// Every point of the grid, row after row
+    pub fn positions(&self) -> impl Iterator<Item = Point> + use<T> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// First point, row after row, of a cell matching the predicate
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        let i = self.cells.iter().position(predicate)?;
+        Some(Point::new((i % self.width) as i64, (i / self.width) as i64))
+    }
+
+    /// Moves a point one step in a direction, if it stays in the grid
+    pub fn offset(&self, point: Point, direction: Direction) -> Option<Point> {
+        Some(point + direction).filter(|p| self.contains(*p))
+    }
+
+    /// Points next to a cell, up, right, down and left, that are in the grid
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL4
+            .into_iter()
+            .filter_map(move |direction| self.offset(point, direction))
+    }
+
+    /// Points around a cell, diagonals included, that are in the grid
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL8
+            .into_iter()
+            .filter_map(move |direction| self.offset(point, direction))
+    }
+
+    /// Points from a cell, excluded, to the edge of the grid, moving in a direction
+    pub fn ray(&self, point: Point, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(self.offset(point, direction), move |p| {
+            self.offset(*p, direction)
+        })
+    }
+
+    // Panics like indexing with a point outside of the grid
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "Row {y} is outside of the grid");
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    // Panics like row, rather than going on with the cells of the next rows
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {x} is outside of the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Rows become columns
+    pub fn transpose(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    /// Quarter turn, the first column becomes the first row read from the bottom
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self
+                .columns()
+                .flat_map(|column| column.collect::<Vec<_>>().into_iter().rev())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|x| self.column(x))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        self.rows().map(<[T]>::to_vec).collect()
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("Point {point} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("Point {point} is outside of the grid"))
+    }
+}
+
+// One line per row, cells written next to each other
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10).ok_or("Not a digit")).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&6));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let error = Grid::parse("12\n3x", |c| c.to_digit(10).ok_or("Not a digit")).unwrap_err();
+        assert_eq!(error, AocError::parse("Not a digit").at_line(2).at_col(2));
+        let error = Grid::parse("12\n3", |c| c.to_digit(10).ok_or("Not a digit")).unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse("Expected 2 cells, found 1")
+                .at_line(2)
+                .at_col(2)
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+        let values =
+            |points: Vec<Point>| -> Vec<u32> { points.into_iter().map(|p| grid[p]).collect() };
+        assert_eq!(
+            values(grid.neighbours4(Point::new(0, 0)).collect()),
+            vec![2, 4]
+        );
+        assert_eq!(
+            values(grid.neighbours4(Point::new(1, 1)).collect()),
+            vec![2, 6, 8, 4]
+        );
+        assert_eq!(
+            values(grid.neighbours8(Point::new(2, 0)).collect()),
+            vec![6, 5, 2]
+        );
+        assert_eq!(
+            values(grid.ray(Point::new(0, 1), Direction::Right).collect()),
+            vec![5, 6]
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<u32>>(), vec![2, 5]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.position(|c| *c == 5), Some(Point::new(1, 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Column 3 is outside of the grid")]
+    fn test_column_outside() {
+        digits("123\n456").column(3).count();
+    }
+
+    #[test]
+    #[should_panic(expected = "Row 2 is outside of the grid")]
+    fn test_row_outside() {
+        digits("123\n456").row(2);
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36");
+        assert_eq!(grid.rotate_clockwise().to_string(), "41\n52\n63");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "36\n25\n14");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_counterclockwise(),
+            grid.clone()
+        );
+        assert_eq!(Grid::filled(2, 1, 0).to_string(), "00");
+    }
+}
@@ -0,0 +1,52 @@
+//! Lists kept across runs in a JSON file, such as the submitted answers or the benchmarks
+//! The whole list is read at once and written again after every change, they stay small
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::error::{AocError, Result};
+
+/// The entries recorded in a file, a missing file being an empty history
+/// `what` names the history in the error of a file that can't be read back
+pub fn load<T: DeserializeOwned>(path: &Path, what: &str) -> Result<Vec<T>> {
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| AocError::Io {
+            file: path.display().to_string(),
+            message: format!("Invalid {what}: {e}"),
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(AocError::io(path, e)),
+    }
+}
+
+/// Writes every entry to the file, creating its directory first
+pub fn save<T: Serialize>(path: &Path, entries: &[T]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AocError::io(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(entries).expect("histories hold plain data");
+    fs::write(path, json).map_err(|e| AocError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = TempDir::new("history-file");
+        let path = dir.join("nested").join("numbers.json");
+        assert_eq!(load::<u32>(&path, "numbers").unwrap(), Vec::<u32>::new());
+
+        save(&path, &[1, 2, 3]).unwrap();
+        assert_eq!(load::<u32>(&path, "numbers").unwrap(), vec![1, 2, 3]);
+
+        fs::write(&path, "[1,").unwrap();
+        let error = load::<u32>(&path, "numbers").unwrap_err().to_string();
+        assert!(error.contains("Invalid numbers"), "{error}");
+    }
+}
@@ -0,0 +1,176 @@
+//! Finds the text of puzzle inputs and example files, whatever the working directory
+//!
+//! A bare file name such as "day7" is a puzzle input. It is read from, in order:
+//! - the AOC_INPUT_DIR directory when that variable is set
+//! - the cache filled by `aoc-2022 fetch`
+//! - the inputs embedded in the binary, when built with the `embed-inputs` feature
+//! - src/files in the crate
+//!
+//! Any other relative path, such as "src/examples/day7.txt", is relative to the crate root
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AocError, Result};
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+// Where inputs are stored in the crate
+const FILES_DIR: &str = "src/files";
+
+#[cfg(feature = "embed-inputs")]
+mod embedded {
+    // (file name, content) of every input in src/files, generated by build.rs
+    include!(concat!(env!("OUT_DIR"), "/inputs.rs"));
+
+    pub fn get(name: &str) -> Option<&'static str> {
+        INPUTS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, text)| *text)
+    }
+}
+
+/// Name of the puzzle input of a day
+pub fn input_name(day: u8) -> String {
+    format!("day{day}")
+}
+
+/// Directory of the files downloaded from the website: AOC_CACHE_DIR, or aoc-2022 in the user
+/// cache directory
+pub fn cache_dir() -> Option<PathBuf> {
+    env::var_os(CACHE_DIR_VAR)
+        .map(PathBuf::from)
+        .or_else(|| dirs::cache_dir().map(|dir| dir.join("aoc-2022")))
+}
+
+/// Where a fetched puzzle input is stored in a cache directory
+pub fn cached_input(cache_dir: &Path, name: &str) -> PathBuf {
+    cache_dir.join("inputs").join(name)
+}
+
+fn is_input_name(name: &str) -> bool {
+    Path::new(name).components().count() == 1
+}
+
+// Path of a file on disk, given the directory set by AOC_INPUT_DIR if any
+fn locate_in(name: &str, input_dir: Option<&Path>) -> PathBuf {
+    let path = Path::new(name);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    let crate_root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match input_dir {
+        Some(dir) if is_input_name(name) => dir.join(path),
+        _ if is_input_name(name) => crate_root.join(FILES_DIR).join(path),
+        _ => crate_root.join(path),
+    }
+}
+
+/// Path of a file on disk, ignoring embedded inputs
+pub fn locate(name: &str) -> PathBuf {
+    let input_dir = env::var_os(INPUT_DIR_VAR).map(PathBuf::from);
+    locate_in(name, input_dir.as_deref())
+}
+
+/// Where the text of a file is taken from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    /// Built into the binary with the `embed-inputs` feature
+    Embedded(&'static str),
+}
+
+// Source of a file, given the directories set by AOC_INPUT_DIR and of the cache if any
+fn find_in(name: &str, input_dir: Option<&Path>, cache_dir: Option<&Path>) -> Source {
+    if input_dir.is_none() && is_input_name(name) {
+        let cached = cache_dir.map(|dir| cached_input(dir, name));
+        if let Some(cached) = cached.filter(|path| path.is_file()) {
+            return Source::File(cached);
+        }
+        #[cfg(feature = "embed-inputs")]
+        if let Some(text) = embedded::get(name) {
+            return Source::Embedded(text);
+        }
+    }
+    Source::File(locate_in(name, input_dir))
+}
+
+/// Where read takes the text of a file from, trying the places listed above in order
+pub fn find(name: &str) -> Source {
+    let input_dir = env::var_os(INPUT_DIR_VAR).map(PathBuf::from);
+    find_in(name, input_dir.as_deref(), cache_dir().as_deref())
+}
+
+/// Text of an input or example file
+pub fn read(name: &str) -> Result<String> {
+    match find(name) {
+        Source::Embedded(text) => Ok(text.to_string()),
+        // The error names the path that was tried, so a missing input is easy to place
+        Source::File(path) => fs::read_to_string(&path).map_err(|e| AocError::io(&path, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_locate() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert_eq!(locate_in("day7", None), root.join("src/files/day7"));
+        assert_eq!(
+            locate_in("src/examples/day7.txt", None),
+            root.join("src/examples/day7.txt")
+        );
+        assert_eq!(
+            locate_in("./src/tests/numbers1.txt", None),
+            root.join("./src/tests/numbers1.txt")
+        );
+
+        let dir = Path::new("/tmp/inputs");
+        assert_eq!(locate_in("day7", Some(dir)), dir.join("day7"));
+        assert_eq!(
+            locate_in("src/examples/day7.txt", Some(dir)),
+            root.join("src/examples/day7.txt")
+        );
+        assert_eq!(
+            locate_in("/data/day7", Some(dir)),
+            PathBuf::from("/data/day7")
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        // Day 25 has no input in src/files, which could be embedded
+        let cache = TempDir::new("input-cache");
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let files = root.join("src/files/day25");
+        assert_eq!(
+            find_in("day25", None, Some(cache.path())),
+            Source::File(files)
+        );
+
+        let cached = cached_input(cache.path(), "day25");
+        fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        fs::write(&cached, "1\n").unwrap();
+        assert_eq!(
+            find_in("day25", None, Some(cache.path())),
+            Source::File(cached)
+        );
+
+        // AOC_INPUT_DIR comes first, and only puzzle inputs are cached
+        let dir = Path::new("/tmp/inputs");
+        assert_eq!(
+            find_in("day7", Some(dir), Some(cache.path())),
+            Source::File(dir.join("day7"))
+        );
+        assert_eq!(
+            find_in("src/examples/day7.txt", None, Some(cache.path())),
+            Source::File(root.join("src/examples/day7.txt"))
+        );
+    }
+}
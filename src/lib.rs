@@ -0,0 +1,68 @@
+//! Solutions of Advent of Code 2022, with the tools to fetch inputs and to check and submit answers
+//! The aoc-2022 binary (src/main.rs) is the command line interface over this library: it parses the
+//! arguments and calls the function of each command, such as [`runner::run`] or [`verify::check_answers`]
+//!
+//! Each day is a [`solution::Puzzle`] in [`exercises`], its typed input can be used on its own:
+//!
+//! ```
+//! use aoc_2022::exercises::day4::{Day4, Interval};
+//! use aoc_2022::solution::Puzzle;
+//!
+//! let pairs = Day4::parse("src/examples/day4.txt")?;
+//! assert_eq!(Day4::part1(&pairs)?.to_string(), "2");
+//!
+//! let first: Interval = "2-8".parse()?;
+//! assert!(first.contains(&"3-7".parse()?));
+//! # Ok::<(), aoc_2022::error::AocError>(())
+//! ```
+//!
+//! [`exercises::solutions`] lists every day behind the same [`solution::Solution`] type, which is
+//! what the binary runs. Inputs are read with [`parser`], whose errors locate the offending line:
+//!
+//! ```
+//! use aoc_2022::error::AocError;
+//! use aoc_2022::parser::{AocParse, parse_into_struct};
+//!
+//! #[derive(Debug, AocParse)]
+//! #[aoc(regex = r"(\w+) moved (\d+)")]
+//! struct Move {
+//!     crate_name: String,
+//!     distance: u32,
+//! }
+//!
+//! let lines = ["A moved 3", "B moved far"];
+//! let error = parse_into_struct(lines, Move::aoc_parse).unwrap_err();
+//! assert!(matches!(error, AocError::Parse { location, .. } if location.line == Some(2)));
+//! ```
+
+// Lets the code generated by #[derive(AocParse)] name this crate as its users do
+extern crate self as aoc_2022;
+
+pub mod answer;
+pub mod bench;
+pub mod budget;
+pub mod client;
+pub mod error;
+pub mod examples;
+pub mod exercises;
+pub mod geometry;
+pub mod grid;
+pub mod history;
+pub mod input;
+pub mod output;
+pub mod parser;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod table;
+pub mod verify;
+pub mod watch;
+
+#[cfg(test)]
+mod testing;
+
+// Tests of memory budgets run days in the test binary, which counts allocations as the binary does
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: budget::CountingAllocator = budget::CountingAllocator;
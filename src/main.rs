@@ -1,134 +1,61 @@
-#![feature(slice_as_chunks)]
-#![feature(is_sorted)]
-#![feature(let_chains)]
+use std::{env, process};
 
-use std::env;
-mod exercises;
-mod parser;
+use aoc_2022::budget::CountingAllocator;
+use aoc_2022::solution::Solution;
+use aoc_2022::{bench, client, examples, exercises, runner, scaffold, submit, verify, watch};
+use cli::Command;
 
-fn main() {
-    if let Some(args) = env::args().nth(1) && args == "all" {
-        // Day 1.1
-        let day1_1 = exercises::day1::day_1_1("./src/files/day1.1");
-        println!("Day 1.1 : {day1_1}");
-
-        // Day 1.2
-        let day1_2 = exercises::day1::day_1_2("./src/files/day1.1");
-        println!("Day 1.2 : {day1_2}");
-
-        // Day 2.1
-        let day2_1 = exercises::day2::day_2_1("./src/files/day2");
-        println!("Day 2.1 : {day2_1}");
-
-        // Day 2.2
-        let day2_2 = exercises::day2::day_2_2("./src/files/day2");
-        println!("Day 2.2 : {day2_2}");
-
-        // Day 3.1
-        let day3_1 = exercises::day3::day_3_1("./src/files/day3");
-        println!("Day 3.1: {day3_1}");
-
-        // Day 3.2
-        let day3_2 = exercises::day3::day_3_2("./src/files/day3");
-        println!("Day 3.2: {day3_2}");
-
-        // Day 4.1
-        let day4_1 = exercises::day4::day_4_1("./src/files/day4");
-        println!("Day 4.1: {day4_1}");
-
-        // Day 4.2
-        let day4_2 = exercises::day4::day_4_2("./src/files/day4");
-        println!("Day 4.2: {day4_2}");
-
-        // Day 5.1
-        let day5_1 = exercises::day5::day_5_1("./src/files/day5");
-        print!("Day 5.1: ");
-        for c in day5_1 {
-            print!("{c}");
-        }
-        println!();
-
-        // Day 5.2
-        let day5_2 = exercises::day5::day_5_2("./src/files/day5");
-        print!("Day 5.2: ");
-        for c in day5_2 {
-            print!("{c}");
-        }
-        println!();
-
-        // Day 6.1
-        let day6_1 = exercises::day6::day_6_1("./src/files/day6");
-        println!("Day 6.1: {}", day6_1.first().unwrap());
-
-        // Day 6.2
-        let day6_2 = exercises::day6::day_6_2("./src/files/day6");
-        println!("Day 6.2: {}", day6_2.first().unwrap());
-
-        // Day 7.1
-        let day7_1 = exercises::day7::day_7_1("./src/files/day7");
-        println!("Day 7.1: {day7_1}");
+mod cli;
 
-        // Day 7.2
-        let day7_2 = exercises::day7::day_7_2("./src/files/day7");
-        println!("Day 7.2: {day7_2}");
+// Counts the memory used by each day's process, to enforce the memory budget of `run`
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
-        // Day 8.1
-        let day8_1 = exercises::day8::day_8_1("./src/files/day8", 99);
-        println!("Day 8.1: {day8_1}");
-
-        // Day 8.2
-        let day8_2 = exercises::day8::day_8_2("./src/files/day8", 99);
-        println!("Day 8.2: {day8_2}");
-
-        // Day 9.1
-        let day9_1 = exercises::day9::day_9_1("./src/files/day9");
-        println!("Day 9.1: {day9_1}");
-
-        // Day 9.2
-        let day9_2 = exercises::day9::day_9_2("./src/files/day9", 10);
-        println!("Day 9.2: {day9_2}");
-
-        // Day 10.1
-        let day10_1 = exercises::day10::day_10_1("./src/files/day10");
-        println!("Day 10.1: {day10_1}");
-
-        // Day 10.2
-        let day10_2 = exercises::day10::day_10_2("./src/files/day10");
-        println!("Day 10.2:");
-        for i in day10_2 {
-            for j in i {
-                if j {
-                    print!("#")
-                } else {
-                    print!(".")
-                }
-            }
-            println!();
+fn main() {
+    // Started again by `run` to solve a single day
+    if runner::serve_worker(&exercises::solutions()) {
+        return;
+    }
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = cli::parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{e}\n\n{}", cli::USAGE);
+        process::exit(2);
+    });
+
+    let succeeded = match command {
+        Command::Run(selection, options) => runner::run(
+            &solutions(|day| selection.contains_day(day)),
+            &selection.parts(),
+            &options,
+        ),
+        Command::Fetch { day } => client::fetch(day),
+        Command::Submit { day, part } => solution(day).is_some_and(|s| submit::submit(&s, part)),
+        Command::Verify { days } => verify::check_answers(&solutions(|day| days.contains(&day))),
+        Command::New { day } => scaffold::new_day(day),
+        Command::Examples { day } => solution(day).is_some_and(|s| examples::check_examples(&s)),
+        Command::Watch { day } => watch::watch(day),
+        Command::Bench { days, options } => {
+            bench::bench(&solutions(|day| days.contains(&day)), &options)
         }
-        println!();
-
-        // Day 11.1
-        let day11_1 = exercises::day11::day_11_1("./src/files/day11");
-        println!("Day 11.1: {day11_1}");
-
-        // Day 11.2
-        let day11_2 = exercises::day11::day_11_2("./src/files/day11");
-        println!("Day 11.2: {day11_2}");
-
-        // Day 12.1
-        let day12_1 = exercises::day12::day_12_1("./src/files/day12") - 2;
-        println!("Day 12.1: {day12_1}");
-
-        // Day 12.2
-        let day12_2 = exercises::day12::day_12_2("./src/files/day12") - 2;
-        println!("Day 12.2: {day12_2}");
+    };
+    if !succeeded {
+        process::exit(1);
     }
+}
 
-    // Day 13.1
-    let day13_1 = exercises::day13::day_13_1("./src/files/day13");
-    println!("Day 13.1: {day13_1}");
+// The registered solutions of the selected days, in day order
+fn solutions(selected: impl Fn(u8) -> bool) -> Vec<Solution> {
+    exercises::solutions()
+        .into_iter()
+        .filter(|s| selected(s.day))
+        .collect()
+}
 
-    // Day 13.2
-    let day13_2 = exercises::day13::day_13_2("./src/files/day13");
-    println!("Day 13.2: {day13_2}");
+// The solution of a day, once it is registered
+fn solution(day: u8) -> Option<Solution> {
+    let solution = solutions(|d| d == day).pop();
+    if solution.is_none() {
+        eprintln!("Day {day}: not solved yet");
+    }
+    solution
 }
@@ -0,0 +1,236 @@
+//! Machine readable results of a run, one record per day and part
+//! Scripts and dashboards read these rather than the lines printed for humans
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::budget;
+use crate::runner::{DayRun, Outcome};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Answers as `Day 7.1: 1234` lines, followed by a report
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format {s}, expected text, json or csv")),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Format::Text => "text",
+            Format::Json => "json",
+            Format::Csv => "csv",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Ok,
+    Error,
+    Panic,
+    Timeout,
+    OverBudget,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Status::Ok => "ok",
+            Status::Error => "error",
+            Status::Panic => "panic",
+            Status::Timeout => "timeout",
+            Status::OverBudget => "over-budget",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The result of a part, durations are in nanoseconds
+/// Parsing is shared by both parts of a day, so both records have the same parse_ns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartRecord {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+    /// As printed, screens are lines of # and .
+    pub answer: Option<String>,
+    pub parse_ns: Option<u64>,
+    pub solve_ns: Option<u64>,
+    /// Why there is no answer
+    pub message: Option<String>,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+/// Days that did not finish give a record per part with the reason they were stopped
+pub fn records(runs: &[DayRun], parts: &[u8]) -> Vec<PartRecord> {
+    let mut records = vec![];
+    for run in runs {
+        for (index, &part) in parts.iter().enumerate() {
+            let mut record = PartRecord {
+                day: run.day,
+                part,
+                status: Status::Ok,
+                answer: None,
+                parse_ns: None,
+                solve_ns: None,
+                message: None,
+            };
+            match &run.outcome {
+                Outcome::Finished(answers, timings) => {
+                    record.parse_ns = Some(nanos(timings.parse));
+                    record.solve_ns = timings.parts.get(index).copied().map(nanos);
+                    match &answers[index] {
+                        Ok(answer) => record.answer = Some(answer.to_string()),
+                        Err(e) => {
+                            record.status = Status::Error;
+                            record.message = Some(e.to_string());
+                        }
+                    }
+                }
+                Outcome::Panicked(message) => {
+                    record.status = Status::Panic;
+                    record.message = Some(message.clone());
+                }
+                Outcome::TimedOut(timeout) => {
+                    record.status = Status::Timeout;
+                    record.message = Some(format!("still running after {timeout:.1?}"));
+                }
+                Outcome::OverBudget(memory) => {
+                    record.status = Status::OverBudget;
+                    let memory = budget::format_bytes(*memory);
+                    record.message = Some(format!("needed more than {memory}"));
+                }
+            }
+            records.push(record);
+        }
+    }
+    records
+}
+
+/// A JSON array of records
+pub fn render_json(records: &[PartRecord]) -> String {
+    serde_json::to_string_pretty(records).expect("records can be written as JSON")
+}
+
+// Fields with commas, quotes or line breaks are quoted, as in RFC 4180
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// A header line, then one line per record, missing values are left empty
+pub fn render_csv(records: &[PartRecord]) -> String {
+    let mut lines = vec!["day,part,status,answer,parse_ns,solve_ns,message".to_string()];
+    for record in records {
+        let number = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_default();
+        let fields = [
+            record.day.to_string(),
+            record.part.to_string(),
+            record.status.to_string(),
+            record.answer.clone().unwrap_or_default(),
+            number(record.parse_ns),
+            number(record.solve_ns),
+            record.message.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        lines.push(fields.join(","));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::answer::Answer;
+    use crate::error::AocError;
+    use crate::solution::Timings;
+
+    fn runs() -> Vec<DayRun> {
+        let micros = Duration::from_micros;
+        let run = |day, outcome| DayRun {
+            day,
+            elapsed: micros(500),
+            peak_memory: None,
+            outcome,
+        };
+        vec![
+            run(
+                5,
+                Outcome::Finished(
+                    vec![Ok("CMZ".into()), Err(AocError::solve("Empty stack 3"))],
+                    Timings {
+                        parse: micros(120),
+                        parts: vec![micros(3), micros(4)],
+                    },
+                ),
+            ),
+            run(
+                10,
+                Outcome::Finished(
+                    vec![
+                        Ok(Answer::Integer(13140)),
+                        Ok(Answer::Grid(vec![vec![true, false], vec![false, true]])),
+                    ],
+                    Timings {
+                        parse: micros(30),
+                        parts: vec![micros(1), micros(2)],
+                    },
+                ),
+            ),
+            run(
+                6,
+                Outcome::Panicked("called `Option::unwrap()` on a `None` value".into()),
+            ),
+            run(12, Outcome::TimedOut(Duration::from_secs(60))),
+        ]
+    }
+
+    #[test]
+    fn test_records() {
+        let records = records(&runs(), &[1, 2]);
+        assert_eq!(records.len(), 8);
+        assert_eq!(records[0].answer.as_deref(), Some("CMZ"));
+        assert_eq!(records[1].status, Status::Error);
+        assert_eq!(records[1].solve_ns, Some(4000));
+        assert_eq!(records[7].status, Status::Timeout);
+    }
+
+    #[test]
+    fn test_render_json() {
+        assert_snapshot!(render_json(&records(&runs()[..2], &[1, 2])));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_snapshot!(render_csv(&records(&runs(), &[1, 2])));
+    }
+}
@@ -1,68 +1,304 @@
-use std::fs;
+use std::iter::Enumerate;
+use std::str::Lines;
 
-use regex::Regex;
+pub use aoc_derive::AocParse;
+pub use regex::Regex;
+use serde::de::DeserializeOwned;
 
-// Parses an aoc file into a vector of strings
-// By defaults it splits on newlines, but it can be changed easily
-pub fn parse_aoc_file(filename: &str, delimiter: Option<&str>) -> Vec<String> {
-    let Ok(file) = fs::read_to_string(filename) else {
-        panic!("Could not read file {filename}");
-    };
+use crate::error::{AocError, Result};
+use crate::input;
+
+pub mod combinators;
+
+/// Reads a whole aoc file at once
+/// Lines and blocks are then borrowed from it instead of being copied, see lines and blocks
+pub fn read_aoc_file(filename: &str) -> Result<String> {
+    input::read(filename)
+}
+
+/// Lines of an input, borrowed from it, lazily and in one pass
+/// Lines end with "\n" or "\r\n", a trailing line break yields no empty line
+pub fn lines(input: &str) -> Lines<'_> {
+    input.lines()
+}
+
+/// Parses an aoc file into a vector of strings, copying every line
+/// By defaults it splits on newlines, but it can be changed easily
+pub fn parse_aoc_file(filename: &str, delimiter: Option<&str>) -> Result<Vec<String>> {
+    let file = read_aoc_file(filename)?;
     let split: Vec<&str> = file.split(delimiter.unwrap_or("\n")).collect();
 
-    return split.iter().map(|s| s.to_string()).collect();
+    Ok(split.iter().map(|s| s.to_string()).collect())
+}
+
+/// Compiles a regex, reporting an invalid pattern as an AocError
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| AocError::InvalidPattern {
+        pattern: pattern.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Parses strings with the given parser function
+/// Errors are located on the line they come from, counting from 1
+/// A parser reading several lines at once locates its errors from its first line
+pub fn parse_into_struct<S, T>(
+    data: impl IntoIterator<Item = S>,
+    parser: fn(S) -> Result<T>,
+) -> Result<Vec<T>> {
+    let mut result = Vec::new();
+    for (i, line) in data.into_iter().enumerate() {
+        result.push(parser(line).map_err(|e| e.offset_lines(i).at_line(i + 1))?);
+    }
+    Ok(result)
 }
 
-// Parses strings with the given parser function
-pub fn parse_into_struct<T>(data: Vec<String>, parser: fn(String) -> T) -> Vec<T> {
+/// Parses every line with the given parser, which can capture its context
+/// Unlike parse_into_struct, all the failing lines are reported together, each with its line number
+pub fn parse_into_struct_with<S, T, E>(
+    data: impl IntoIterator<Item = S>,
+    mut parser: impl FnMut(&str) -> std::result::Result<T, E>,
+) -> Result<Vec<T>>
+where
+    S: AsRef<str>,
+    E: Into<AocError>,
+{
     let mut result = Vec::new();
-    for line in data {
-        result.push(parser(line));
+    let mut errors = Vec::new();
+    for (i, line) in data.into_iter().enumerate() {
+        match parser(line.as_ref()) {
+            Ok(value) => result.push(value),
+            Err(e) => errors.push(e.into().offset_lines(i).at_line(i + 1)),
+        }
+    }
+    match errors.len() {
+        0 => Ok(result),
+        1 => Err(errors.remove(0)),
+        _ => Err(AocError::Multiple(errors)),
+    }
+}
+
+/// A type read from a text with a regex, one field per capture group
+/// The regex must match the whole text, text left before or after it is an error at its position
+/// Derive it with #[derive(AocParse)] #[aoc(regex = "...")], then give T::aoc_parse to parse_into_struct
+pub trait AocParse: Sized {
+    fn aoc_parse(text: &str) -> Result<Self>;
+}
+
+/// Reads a TOML text such as a manifest, an error pointing at its line and column in the file
+pub fn parse_toml<T: DeserializeOwned>(text: &str, file: &str) -> Result<T> {
+    toml::from_str(text).map_err(|e| {
+        let error = AocError::parse(e.message().to_string()).in_file(file);
+        match e.span() {
+            Some(span) => {
+                let (line, col) = text_position(text, span.start);
+                error.at_line(line).at_col(col)
+            }
+            None => error,
+        }
+    })
+}
+
+// Line and column of a byte offset in a text, both starting at 1
+fn text_position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let col = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, col)
+}
+
+/// Used by the code generated by #[derive(AocParse)], not meant to be called directly
+#[doc(hidden)]
+pub mod __private {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    pub use regex::{Captures, Regex};
+
+    pub use super::compile_pattern;
+    use super::text_position;
+    use crate::error::{AocError, Result};
+
+    // The match must cover the whole text, the text left unread is reported where it starts
+    pub fn match_pattern<'a>(pattern: &Regex, text: &'a str, name: &str) -> Result<Captures<'a>> {
+        let captures = pattern.captures(text).ok_or_else(|| {
+            AocError::parse(format!("Expected {name} matching {:?}", pattern.as_str()))
+                .at_line(1)
+                .at_col(1)
+        })?;
+        let whole = captures.get(0).expect("group 0 is the whole match");
+        if whole.start() > 0 {
+            return Err(AocError::parse(format!("Unexpected text before {name}"))
+                .at_line(1)
+                .at_col(1));
+        }
+        let rest = &text[whole.end()..];
+        if !rest.is_empty() {
+            // Pointing at the unread text rather than at the blanks before it
+            let offset = text.len() - rest.trim_start().len();
+            let (line, col) = text_position(text, offset);
+            return Err(AocError::parse(format!("Unexpected text after {name}"))
+                .at_line(line)
+                .at_col(col));
+        }
+        Ok(captures)
+    }
+
+    pub fn parse_capture<T>(text: &str, captures: &Captures, index: usize, field: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(capture) = captures.get(index) else {
+            return Err(AocError::parse(format!("Missing {field}"))
+                .at_line(1)
+                .at_col(1));
+        };
+        parse_located(text, capture.as_str(), capture.start(), field)
+    }
+
+    pub fn parse_capture_list<T>(
+        text: &str,
+        captures: &Captures,
+        index: usize,
+        field: &str,
+        separator: &str,
+    ) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(capture) = captures.get(index) else {
+            return Err(AocError::parse(format!("Missing {field}"))
+                .at_line(1)
+                .at_col(1));
+        };
+        let mut offset = capture.start();
+        let mut result = vec![];
+        for element in capture.as_str().split(separator) {
+            result.push(parse_located(text, element, offset, field)?);
+            offset += element.len() + separator.len();
+        }
+        Ok(result)
+    }
+
+    fn parse_located<T>(text: &str, value: &str, offset: usize, field: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        value.parse().map_err(|e| {
+            let (line, col) = text_position(text, offset);
+            AocError::parse(format!("Invalid {field} `{value}`: {e}"))
+                .at_line(line)
+                .at_col(col)
+        })
     }
-    result
 }
 
-// Reads through a vector of lines while not matching a specific regex
-pub fn parse_until_pattern(data: Vec<String>, pattern: &str) -> (Vec<String>, Vec<String>) {
-    let Ok(re) = Regex::new(pattern) else {
-        panic!("Could not parse pattern {pattern} at parse_until_pattern");
-    };
-    let mut index = 0;
-    for line in &data {
-        if re.is_match(line) {
-            break;
+/// Reads through a vector of lines while not matching a specific regex
+/// The vector is split in place, no line is copied
+pub fn parse_until_pattern<S: AsRef<str>>(
+    mut data: Vec<S>,
+    pattern: &str,
+) -> Result<(Vec<S>, Vec<S>)> {
+    let re = compile_pattern(pattern)?;
+    let index = data
+        .iter()
+        .position(|line| re.is_match(line.as_ref()))
+        .unwrap_or(data.len());
+    let rest = data.split_off(index);
+    Ok((data, rest))
+}
+
+/// Consecutive non blank lines of an input, borrowed from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block<'a> {
+    /// Line number of the first line of the block in the input, counting from 1
+    pub first_line: usize,
+    pub lines: Vec<&'a str>,
+}
+
+/// Iterator over the blocks of an input, see blocks
+pub struct Blocks<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = Block<'a>;
+
+    fn next(&mut self) -> Option<Block<'a>> {
+        let mut block: Option<Block<'a>> = None;
+        for (i, line) in self.lines.by_ref() {
+            if line.trim().is_empty() {
+                if block.is_some() {
+                    break;
+                }
+                continue;
+            }
+            block
+                .get_or_insert_with(|| Block {
+                    first_line: i + 1,
+                    lines: Vec::new(),
+                })
+                .lines
+                .push(line);
         }
-        index += 1;
+        block
+    }
+}
+
+/// Splits an input into blocks separated by blank lines, lazily and in one pass
+/// Lines end with "\n" or "\r\n", runs of blank lines and trailing blank lines yield no empty block
+pub fn blocks(input: &str) -> Blocks<'_> {
+    Blocks {
+        lines: input.lines().enumerate(),
     }
-    (data[0..index].to_vec(), data[index..].to_vec())
+}
+
+/// Splits an input into its blocks of lines, each block keeping where it starts in the input
+/// Use blocks to go through them without collecting
+pub fn parse_blocks(input: &str) -> Vec<Block<'_>> {
+    blocks(input).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use insta::assert_snapshot;
 
+    #[allow(dead_code)]
     #[derive(Debug)]
     struct TestStruct {
         a: String,
         b: String,
     }
 
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(regex = r"(\w+) has ([0-9, ]+)\n(\d+) left")]
+    struct DerivedStruct {
+        name: String,
+        #[aoc(split = ", ")]
+        items: Vec<u8>,
+        left: u8,
+        #[aoc(skip)]
+        seen: bool,
+    }
+
     #[test]
     fn test_parse_aoc_file_default() {
-        let data = parse_aoc_file("./src/tests/numbers1.txt", None);
+        let data = parse_aoc_file("./src/tests/numbers1.txt", None).unwrap();
         insta::assert_debug_snapshot!(data);
     }
 
     #[test]
     fn test_parse_aoc_file_comma() {
-        let data = parse_aoc_file("./src/tests/numbers2.txt", Some(","));
+        let data = parse_aoc_file("./src/tests/numbers2.txt", Some(",")).unwrap();
         insta::assert_debug_snapshot!(data);
     }
 
     #[test]
     fn test_parse_aoc_file_with_blank() {
-        let data = parse_aoc_file("./src/tests/numbers3.txt", None);
+        let data = parse_aoc_file("./src/tests/numbers3.txt", None).unwrap();
         insta::assert_debug_snapshot!(data);
     }
 
@@ -71,11 +307,12 @@ mod tests {
         let data = vec!["1,2".to_string(), "3,4".to_string()];
         let result = parse_into_struct(data, |s| {
             let split: Vec<&str> = s.split(',').collect();
-            TestStruct {
+            Ok(TestStruct {
                 a: split[0].to_string(),
                 b: split[1].to_string(),
-            }
-        });
+            })
+        })
+        .unwrap();
 
         insta::assert_debug_snapshot!(result);
     }
@@ -83,30 +320,167 @@ mod tests {
     #[test]
     fn test_parse_into_simple() {
         let data = vec!["1".to_string(), "2".to_string()];
-        let result = parse_into_struct(data, |s| s.parse::<i32>().unwrap());
+        let result = parse_into_struct(data, |s| Ok(s.parse::<i32>().unwrap())).unwrap();
 
         insta::assert_debug_snapshot!(result);
     }
 
     #[test]
     fn test_parse_until() {
-        let data = vec!["aaaa", "aaaa", "aaaa", "bbbb", "bbbb", "cccc"]
+        let data = ["aaaa", "aaaa", "aaaa", "bbbb", "bbbb", "cccc"]
             .iter()
             .map(|s| s.to_string())
             .collect();
-        let (first, second) = parse_until_pattern(data, "bbbb");
+        let (first, second) = parse_until_pattern(data, "bbbb").unwrap();
 
         insta::assert_debug_snapshot!(vec![first, second]);
     }
 
     #[test]
     fn test_parse_empty() {
-        let data = vec!["1", "", "2", "3"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let (first, second) = parse_until_pattern(data, r"^$");
+        let data = ["1", "", "2", "3"].iter().map(|s| s.to_string()).collect();
+        let (first, second) = parse_until_pattern(data, r"^$").unwrap();
 
         insta::assert_debug_snapshot!(vec![first, second]);
     }
+
+    #[test]
+    fn test_parse_missing_file() {
+        let error = parse_aoc_file("./src/tests/missing.txt", None).unwrap_err();
+        assert!(matches!(error, AocError::Io { .. }));
+    }
+
+    #[test]
+    fn test_parse_into_struct_error_line() {
+        let data = vec!["1".to_string(), "a".to_string()];
+        let error = parse_into_struct(data, |s| {
+            s.parse::<i32>()
+                .map_err(|_| AocError::parse(format!("Invalid number {s}")))
+        })
+        .unwrap_err();
+
+        assert_eq!(error, AocError::parse("Invalid number a").at_line(2));
+    }
+
+    #[test]
+    fn test_parse_until_invalid_pattern() {
+        let data = vec!["1".to_string()];
+        let error = parse_until_pattern(data, "(").unwrap_err();
+        assert!(matches!(error, AocError::InvalidPattern { .. }));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "1\r\n2\r\n\r\n\r\n3\n  \n4\n5\n\n\n";
+        let result: Vec<Block> = blocks(input).collect();
+        assert_eq!(
+            result,
+            vec![
+                Block {
+                    first_line: 1,
+                    lines: vec!["1", "2"]
+                },
+                Block {
+                    first_line: 5,
+                    lines: vec!["3"]
+                },
+                Block {
+                    first_line: 7,
+                    lines: vec!["4", "5"]
+                },
+            ]
+        );
+        assert_eq!(blocks("\n\n").next(), None);
+    }
+
+    #[test]
+    fn test_parse_blocks_crlf() {
+        let input = read_aoc_file("./src/tests/blocks.txt").unwrap();
+        insta::assert_debug_snapshot!(parse_blocks(&input));
+    }
+
+    #[test]
+    fn test_derive_aoc_parse() {
+        let data = ["bob has 1, 2\n3 left"];
+        let result = parse_into_struct(data, DerivedStruct::aoc_parse).unwrap();
+        assert_eq!(
+            result,
+            vec![DerivedStruct {
+                name: "bob".to_string(),
+                items: vec![1, 2],
+                left: 3,
+                seen: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_derive_aoc_parse_errors() {
+        let data = ["bob has 1, 2\n3 left", "bob has 1, 2222\n3 left"];
+        let error = parse_into_struct(data, DerivedStruct::aoc_parse).unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse("Invalid items `2222`: number too large to fit in target type")
+                .at_line(2)
+                .at_col(12)
+        );
+
+        let error = DerivedStruct::aoc_parse("bob has 1\n300 left").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:1: Invalid left `300`: number too large to fit in target type"
+        );
+
+        let error = DerivedStruct::aoc_parse("bob").unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse(
+                r#"Expected DerivedStruct matching "(\\w+) has ([0-9, ]+)\\n(\\d+) left""#
+            )
+            .at_line(1)
+            .at_col(1)
+        );
+    }
+
+    #[test]
+    fn test_derive_aoc_parse_whole_text() {
+        let error = DerivedStruct::aoc_parse("so bob has 1, 2\n3 left").unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse("Unexpected text before DerivedStruct")
+                .at_line(1)
+                .at_col(1)
+        );
+
+        let error = DerivedStruct::aoc_parse("bob has 1, 2\n3 left over").unwrap_err();
+        assert_eq!(
+            error,
+            AocError::parse("Unexpected text after DerivedStruct")
+                .at_line(2)
+                .at_col(8)
+        );
+    }
+
+    #[test]
+    fn test_parse_into_struct_with_errors() {
+        let mut seen = 0;
+        let error = parse_into_struct_with(["1", "a", "3", "b"], |s| {
+            seen += 1;
+            s.parse::<i32>()
+        })
+        .unwrap_err();
+
+        assert_eq!(seen, 4);
+        assert_eq!(
+            error,
+            AocError::Multiple(vec![
+                AocError::parse("invalid digit found in string").at_line(2),
+                AocError::parse("invalid digit found in string").at_line(4),
+            ])
+        );
+        assert_eq!(
+            parse_into_struct_with(vec!["1".to_string()], |s| s.parse::<i32>()),
+            Ok(vec![1])
+        );
+    }
 }
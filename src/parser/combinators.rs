@@ -0,0 +1,320 @@
+//! Small parser combinators, to read structured lines without writing a state machine
+//! A parser takes the remaining input and returns what it read with the input left after it
+//! Parsers are plain functions or closures, a recursive parser is a function calling itself:
+//!
+//! ```
+//! # use aoc_2022::parser::combinators::*;
+//! fn list(input: &str) -> PResult<'_, Vec<u32>> {
+//!     delimited(literal("["), separated(integer, literal(",")), literal("]"))(input)
+//! }
+//!
+//! assert_eq!(list("[1,2,3]"), Ok((vec![1, 2, 3], "")));
+//! ```
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::{AocError, Result};
+
+/// Where a parser failed and what it expected there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    pub at: &'a str,
+    pub expected: String,
+}
+
+pub type PResult<'a, T> = std::result::Result<(T, &'a str), Failure<'a>>;
+
+fn fail<'a, T>(at: &'a str, expected: impl Into<String>) -> PResult<'a, T> {
+    Err(Failure {
+        at,
+        expected: expected.into(),
+    })
+}
+
+/// Runs a parser on a whole line, errors are located on the column where the parser failed
+pub fn parse_complete<'a, T>(
+    line: &'a str,
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+) -> Result<T> {
+    let failure = match parser(line) {
+        Ok((value, "")) => return Ok(value),
+        Ok((_, rest)) => Failure {
+            at: rest,
+            expected: "end of line".to_string(),
+        },
+        Err(failure) => failure,
+    };
+    // Failures point inside the line, so their distance to its start is their position
+    let offset = (failure.at.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|offset| line.is_char_boundary(*offset))
+        .unwrap_or(0);
+    let col = line[..offset].chars().count() + 1;
+    Err(AocError::parse(format!("Expected {}", failure.expected)).at_col(col))
+}
+
+/// Exactly the given text
+pub fn literal<'a>(expected: &'static str) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input| match input.strip_prefix(expected) {
+        Some(rest) => Ok((&input[..expected.len()], rest)),
+        None => fail(input, format!("{expected:?}")),
+    }
+}
+
+/// A base 10 integer, negative if it starts with '-'
+pub fn integer<T>(input: &str) -> PResult<'_, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let sign = usize::from(input.starts_with('-'));
+    let digits = input[sign..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len() - sign);
+    if digits == 0 {
+        return fail(input, "a number");
+    }
+    let (number, rest) = input.split_at(sign + digits);
+    match number.parse() {
+        Ok(value) => Ok((value, rest)),
+        Err(e) => fail(input, format!("a valid number ({e})")),
+    }
+}
+
+/// Any single character
+pub fn any_char(input: &str) -> PResult<'_, char> {
+    let mut chars = input.chars();
+    match chars.next() {
+        Some(c) => Ok((c, chars.as_str())),
+        None => fail(input, "a character"),
+    }
+}
+
+/// Zero or more spaces
+pub fn spaces(input: &str) -> PResult<'_, &str> {
+    let rest = input.trim_start_matches(' ');
+    Ok((&input[..input.len() - rest.len()], rest))
+}
+
+pub fn map<'a, T, U>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    f: impl Fn(T) -> U,
+) -> impl Fn(&'a str) -> PResult<'a, U> {
+    move |input| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+/// The first parser, or the second one if the first fails
+/// The failure that read the furthest is kept: when both fail, and when the first one failed
+/// further into the input than the second one read, such as in the middle of a malformed item
+pub fn either<'a, T>(
+    first: impl Fn(&'a str) -> PResult<'a, T>,
+    second: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input| {
+        let first_failure = match first(input) {
+            Ok(result) => return Ok(result),
+            Err(failure) => failure,
+        };
+        let second_failure = match second(input) {
+            Ok((value, rest))
+                if first_failure.at.len() == input.len()
+                    || rest.len() <= first_failure.at.len() =>
+            {
+                return Ok((value, rest));
+            }
+            Ok(_) => return Err(first_failure),
+            Err(failure) => failure,
+        };
+        match first_failure.at.len().cmp(&second_failure.at.len()) {
+            std::cmp::Ordering::Less => Err(first_failure),
+            std::cmp::Ordering::Greater => Err(second_failure),
+            std::cmp::Ordering::Equal => fail(
+                first_failure.at,
+                format!("{} or {}", first_failure.expected, second_failure.expected),
+            ),
+        }
+    }
+}
+
+/// The inner parser, between open and close
+pub fn delimited<'a, O, T, C>(
+    open: impl Fn(&'a str) -> PResult<'a, O>,
+    inner: impl Fn(&'a str) -> PResult<'a, T>,
+    close: impl Fn(&'a str) -> PResult<'a, C>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input| {
+        let (_, rest) = open(input)?;
+        let (value, rest) = inner(rest)?;
+        let (_, rest) = close(rest)?;
+        Ok((value, rest))
+    }
+}
+
+/// Zero or more items with a separator between them
+/// An item must follow every separator, so "1,2," fails after the last comma
+pub fn separated<'a, T, S>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+    separator: impl Fn(&'a str) -> PResult<'a, S>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input| {
+        let mut items = vec![];
+        let Ok((first, mut rest)) = item(input) else {
+            return Ok((items, input));
+        };
+        items.push(first);
+        while let Ok((_, after_separator)) = separator(rest) {
+            let (value, after_item) = item(after_separator)?;
+            items.push(value);
+            rest = after_item;
+        }
+        Ok((items, rest))
+    }
+}
+
+/// Cells of a column-aligned layout: each cell is `width` characters wide, cells are separated by
+/// `gap` spaces, and the cell parser must read its whole cell
+/// The last cell may be shorter when the line has no trailing spaces
+pub fn aligned<'a, T>(
+    width: usize,
+    gap: usize,
+    cell: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input| {
+        let mut cells = vec![];
+        let mut rest = input;
+        while !rest.is_empty() {
+            let end = rest
+                .char_indices()
+                .nth(width)
+                .map_or(rest.len(), |(i, _)| i);
+            let (value, left) = cell(&rest[..end])?;
+            if !left.is_empty() {
+                return fail(left, "end of cell");
+            }
+            cells.push(value);
+            rest = &rest[end..];
+            if rest.is_empty() {
+                break;
+            }
+            let separator = rest.chars().take(gap).take_while(|c| *c == ' ').count();
+            if separator != gap {
+                return fail(&rest[separator..], format!("{gap} spaces between cells"));
+            }
+            rest = &rest[gap..];
+        }
+        Ok((cells, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Nested {
+        Int(i32),
+        List(Vec<Nested>),
+    }
+
+    fn nested(input: &str) -> PResult<'_, Nested> {
+        either(
+            map(integer, Nested::Int),
+            map(
+                delimited(literal("["), separated(nested, literal(",")), literal("]")),
+                Nested::List,
+            ),
+        )(input)
+    }
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(integer::<i32>("-12,3"), Ok((-12, ",3")));
+        assert_eq!(parse_complete("42", integer::<u8>), Ok(42));
+        assert_eq!(
+            parse_complete("x", integer::<u8>),
+            Err(AocError::parse("Expected a number").at_col(1))
+        );
+        assert_eq!(
+            parse_complete("300", integer::<u8>),
+            Err(AocError::parse(
+                "Expected a valid number (number too large to fit in target type)"
+            )
+            .at_col(1))
+        );
+    }
+
+    #[test]
+    fn test_separated() {
+        let parser = separated(integer::<u32>, literal(", "));
+        assert_eq!(parse_complete("1, 2, 3", &parser), Ok(vec![1, 2, 3]));
+        assert_eq!(parse_complete("", &parser), Ok(vec![]));
+        assert_eq!(
+            parse_complete("1, 2, ", &parser),
+            Err(AocError::parse("Expected a number").at_col(7))
+        );
+    }
+
+    #[test]
+    fn test_recursive() {
+        assert_eq!(
+            parse_complete("[1,[],[2,[3]]]", nested),
+            Ok(Nested::List(vec![
+                Nested::Int(1),
+                Nested::List(vec![]),
+                Nested::List(vec![Nested::Int(2), Nested::List(vec![Nested::Int(3)])]),
+            ]))
+        );
+        assert_eq!(
+            parse_complete("[1,a]", nested),
+            Err(AocError::parse(r#"Expected a number or "[""#).at_col(4))
+        );
+        assert_eq!(
+            parse_complete("[1]]", nested),
+            Err(AocError::parse("Expected end of line").at_col(4))
+        );
+    }
+
+    #[test]
+    fn test_aligned() {
+        let crate_cell = either(
+            map(delimited(literal("["), any_char, literal("]")), Some),
+            map(spaces, |_| None),
+        );
+        let parser = aligned(3, 1, crate_cell);
+        assert_eq!(
+            parse_complete("    [D]    ", &parser),
+            Ok(vec![None, Some('D'), None])
+        );
+        assert_eq!(
+            parse_complete("[Z] [M]", &parser),
+            Ok(vec![Some('Z'), Some('M')])
+        );
+        assert_eq!(
+            parse_complete("[Z][M]", &parser),
+            Err(AocError::parse("Expected 1 spaces between cells").at_col(4))
+        );
+        assert_eq!(
+            parse_complete("[Z] (M)", &parser),
+            Err(AocError::parse("Expected end of cell").at_col(5))
+        );
+    }
+
+    #[test]
+    fn test_aligned_malformed_cell() {
+        let crate_cell = either(
+            map(delimited(literal("["), any_char, literal("]")), Some),
+            map(spaces, |_| None),
+        );
+        let parser = aligned(3, 1, crate_cell);
+        // The crate parser read "[M" before failing, further than the blank cell parser
+        assert_eq!(
+            parse_complete("[Z] [MP]", &parser),
+            Err(AocError::parse(r#"Expected "]""#).at_col(7))
+        );
+        assert_eq!(
+            parse_complete("    [", &parser),
+            Err(AocError::parse("Expected a character").at_col(6))
+        );
+    }
+}
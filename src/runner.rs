@@ -0,0 +1,590 @@
+//! Runs several days at once, each in its own process started by a thread of a small pool
+//! A day that panics, crashes or goes over its budget (see budget.rs) is reported as failed, the other days still run
+//! A process past its timeout is killed, so a runaway day does not keep its thread or memory
+//!
+//! The process is the same program started again: its main calls serve_worker before anything else
+
+use std::any::Any;
+use std::env;
+use std::io::{BufRead, BufReader, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Answer;
+use crate::budget::{self, Limits};
+use crate::error::{AocError, Result};
+use crate::output::{self, Format};
+use crate::solution::{self, Solution, Timings};
+use crate::table::{self, Align};
+
+/// What running a day gave
+#[derive(Debug)]
+pub enum Outcome {
+    /// Answers are in the same order as the requested parts
+    Finished(Vec<Result<Answer>>, Timings),
+    /// The message the solver panicked with, or how its process ended without answers
+    Panicked(String),
+    /// Killed while still running after the timeout
+    TimedOut(Duration),
+    /// Stopped before allocating more than the memory budget
+    OverBudget(usize),
+}
+
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    /// Until the day finished or was stopped
+    pub elapsed: Duration,
+    /// Highest memory use of the process running the day, unknown when it was stopped
+    pub peak_memory: Option<usize>,
+    pub outcome: Outcome,
+}
+
+impl DayRun {
+    pub fn succeeded(&self) -> bool {
+        match &self.outcome {
+            Outcome::Finished(answers, _) => answers.iter().all(|answer| answer.is_ok()),
+            Outcome::Panicked(_) | Outcome::TimedOut(_) | Outcome::OverBudget(_) => false,
+        }
+    }
+}
+
+/// How to start the process running a day
+#[derive(Debug, Clone)]
+pub struct Worker {
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+impl Worker {
+    /// A program whose main calls serve_worker with the same solutions
+    pub fn new(program: impl Into<PathBuf>, args: &[&str]) -> Worker {
+        Worker {
+            program: program.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// The program currently running, as used by the binary
+    pub fn current_exe() -> Result<Worker> {
+        let program = env::current_exe().map_err(|e| AocError::io("<current executable>", e))?;
+        Ok(Worker::new(program, &[]))
+    }
+}
+
+// Tells a process it runs a day, as "<day> <parts> <memory>" with "-" for no memory limit
+const WORKER_VAR: &str = "AOC_2022_WORKER";
+// Starts the line the report is printed on, other lines are the solver's own output
+const REPORT_PREFIX: &str = "aoc-2022 report: ";
+
+// What the process running a day sends back
+#[derive(Debug, Serialize, Deserialize)]
+enum Report {
+    Finished {
+        answers: Vec<Result<Answer>>,
+        timings: Timings,
+        peak_memory: usize,
+    },
+    Panicked {
+        message: String,
+        peak_memory: usize,
+    },
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// The day, its parts and the memory limit, as set in WORKER_VAR by run_day
+fn parse_task(task: &str) -> Option<(u8, Vec<u8>, Option<usize>)> {
+    let mut fields = task.split(' ');
+    let day = fields.next()?.parse().ok()?;
+    let parts = fields
+        .next()?
+        .split(',')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let memory = match fields.next()? {
+        "-" => None,
+        memory => Some(memory.parse().ok()?),
+    };
+    Some((day, parts, memory))
+}
+
+fn work(solutions: &[Solution], task: &str) -> Report {
+    let task = parse_task(task).and_then(|(day, parts, memory)| {
+        let solution = solutions.iter().find(|solution| solution.day == day)?;
+        Some((solution, parts, memory))
+    });
+    let Some((solution, parts, memory)) = task else {
+        return Report::Panicked {
+            message: format!("{WORKER_VAR} does not name a known day and its parts"),
+            peak_memory: budget::peak_memory(),
+        };
+    };
+    budget::limit_memory(memory);
+    match panic::catch_unwind(AssertUnwindSafe(|| solution.run_timed(&parts))) {
+        Ok((answers, timings)) => Report::Finished {
+            answers,
+            timings,
+            peak_memory: budget::peak_memory(),
+        },
+        Err(payload) => Report::Panicked {
+            message: panic_message(payload.as_ref()),
+            peak_memory: budget::peak_memory(),
+        },
+    }
+}
+
+/// Runs the day asked for by run_parallel and prints what it gave, when this process is one of its workers
+/// Returns false in any other process, which then goes on as usual
+pub fn serve_worker(solutions: &[Solution]) -> bool {
+    let Ok(task) = env::var(WORKER_VAR) else {
+        return false;
+    };
+    // The panic message is in the report, printing it here too would show it twice
+    panic::set_hook(Box::new(|_| {}));
+    let report = serde_json::to_string(&work(solutions, &task)).expect("reports are plain data");
+    println!("{REPORT_PREFIX}{report}");
+    true
+}
+
+// Passes on the lines of the solver, and keeps the report
+fn read_output(output: impl Read) -> Option<Report> {
+    let mut report = None;
+    for line in BufReader::new(output).lines() {
+        let Ok(line) = line else { break };
+        match line.strip_prefix(REPORT_PREFIX) {
+            Some(json) => report = serde_json::from_str(json).ok(),
+            None => eprintln!("{line}"),
+        }
+    }
+    report
+}
+
+fn run_day(worker: &Worker, solution: &Solution, parts: &[u8], limits: &Limits) -> DayRun {
+    let parts: Vec<String> = parts.iter().map(|part| part.to_string()).collect();
+    let memory = limits
+        .memory
+        .map_or("-".to_string(), |memory| memory.to_string());
+    let start = Instant::now();
+    let stopped = |outcome| DayRun {
+        day: solution.day,
+        elapsed: start.elapsed(),
+        peak_memory: None,
+        outcome,
+    };
+
+    let child = Command::new(&worker.program)
+        .args(&worker.args)
+        .env(
+            WORKER_VAR,
+            format!("{} {} {memory}", solution.day, parts.join(",")),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            return stopped(Outcome::Panicked(format!(
+                "could not start its process: {e}"
+            )));
+        }
+    };
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let (sender, receiver) = mpsc::channel();
+    // The output ends with the process, or once it is killed
+    thread::spawn(move || sender.send(read_output(stdout)));
+    let errors = thread::spawn(move || read_output(stderr));
+
+    let report = match limits.timeout {
+        Some(timeout) => receiver.recv_timeout(timeout.saturating_sub(start.elapsed())),
+        None => receiver.recv().map_err(RecvTimeoutError::from),
+    };
+    if let Err(RecvTimeoutError::Timeout) = report {
+        // It may have ended meanwhile, then there is nothing to kill
+        let _ = child.kill();
+        let _ = child.wait();
+        let _ = errors.join();
+        return stopped(Outcome::TimedOut(limits.timeout.unwrap_or_default()));
+    }
+    let status = child.wait();
+    let _ = errors.join();
+    match (status, report.ok().flatten()) {
+        (Ok(status), _) if status.code() == Some(budget::OVER_BUDGET_STATUS) => {
+            stopped(Outcome::OverBudget(limits.memory.unwrap_or_default()))
+        }
+        (_, Some(report)) => {
+            let (outcome, peak_memory) = match report {
+                Report::Finished {
+                    answers,
+                    timings,
+                    peak_memory,
+                } => (Outcome::Finished(answers, timings), peak_memory),
+                Report::Panicked {
+                    message,
+                    peak_memory,
+                } => (Outcome::Panicked(message), peak_memory),
+            };
+            DayRun {
+                peak_memory: Some(peak_memory),
+                ..stopped(outcome)
+            }
+        }
+        (Ok(status), None) => stopped(Outcome::Panicked(format!(
+            "its process ended without answers ({status})"
+        ))),
+        (Err(e), None) => stopped(Outcome::Panicked(format!(
+            "its process could not be waited for: {e}"
+        ))),
+    }
+}
+
+/// Number of worker threads used by default, one per available core
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Threads take the next day to run in a worker process until there are none left
+/// Runs are returned in the order of the solutions, whichever finished first
+pub fn run_parallel(
+    worker: &Worker,
+    solutions: &[Solution],
+    parts: &[u8],
+    threads: usize,
+    limits: &Limits,
+) -> Vec<DayRun> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, solutions.len().max(1)) {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || {
+                while let Some(solution) = solutions.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let run = run_day(worker, solution, parts, limits);
+                    sender
+                        .send(run)
+                        .expect("runs are received until every worker is done");
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut runs: Vec<DayRun> = receiver.into_iter().collect();
+    runs.sort_by_key(|run| solutions.iter().position(|s| s.day == run.day));
+    runs
+}
+
+/// One line per day with its status, time and memory, then how many succeeded
+pub fn render_report(runs: &[DayRun], elapsed: Duration, threads: usize) -> String {
+    let mut rows = vec![];
+    for run in runs {
+        let (status, detail) = match &run.outcome {
+            Outcome::Finished(answers, _) => match answers.iter().find_map(|a| a.as_ref().err()) {
+                None => ("ok", String::new()),
+                Some(e) => ("ERROR", e.to_string().lines().next().unwrap_or("").into()),
+            },
+            Outcome::Panicked(message) => ("PANIC", message.clone()),
+            Outcome::TimedOut(timeout) => ("TIMEOUT", format!("still running after {timeout:.1?}")),
+            Outcome::OverBudget(memory) => (
+                "MEMORY",
+                format!("needed more than {}", budget::format_bytes(*memory)),
+            ),
+        };
+        rows.push(vec![
+            run.day.to_string(),
+            status.to_string(),
+            format!("{:.1?}", run.elapsed),
+            run.peak_memory
+                .map_or("-".to_string(), budget::format_bytes),
+            detail,
+        ]);
+    }
+    let columns = [
+        ("Day", Align::Right),
+        ("Status", Align::Left),
+        ("Time", Align::Right),
+        ("Memory", Align::Right),
+        ("", Align::Left),
+    ];
+
+    let mut report = vec![table::render(&columns, &rows)];
+    let succeeded = runs.iter().filter(|run| run.succeeded()).count();
+    let plural = if threads == 1 { "" } else { "s" };
+    report.push(format!(
+        "{succeeded} succeeded, {} failed in {elapsed:.1?} on {threads} thread{plural}",
+        runs.len() - succeeded
+    ));
+    report.join("\n")
+}
+
+/// How `aoc-2022 run` runs the selected days
+#[derive(Debug, Default, PartialEq)]
+pub struct RunOptions {
+    /// Print a table of the time taken by parsing and by each part
+    pub time: bool,
+    /// Number of days run at once, one per core when not given
+    pub jobs: Option<usize>,
+    /// Budget of each day
+    pub limits: Limits,
+    pub format: Format,
+}
+
+/// Solves the days in parallel and prints their answers in order once every day is done
+/// Returns whether every part succeeded
+pub fn run(solutions: &[Solution], parts: &[u8], options: &RunOptions) -> bool {
+    let worker = match Worker::current_exe() {
+        Ok(worker) => worker,
+        Err(e) => {
+            eprintln!("{e}");
+            return false;
+        }
+    };
+    let threads = options.jobs.unwrap_or_else(default_threads);
+    let start = Instant::now();
+    let runs = run_parallel(&worker, solutions, parts, threads, &options.limits);
+    let elapsed = start.elapsed();
+
+    match options.format {
+        Format::Text => print_runs(&runs, parts, options, elapsed, threads),
+        Format::Json => println!("{}", output::render_json(&output::records(&runs, parts))),
+        Format::Csv => println!("{}", output::render_csv(&output::records(&runs, parts))),
+    }
+    runs.iter().all(DayRun::succeeded)
+}
+
+/// Answers in day order, errors going to stderr, then the timings if asked for and the report
+/// of render_report when several days ran
+pub fn print_runs(
+    runs: &[DayRun],
+    parts: &[u8],
+    options: &RunOptions,
+    elapsed: Duration,
+    threads: usize,
+) {
+    let mut timings: Vec<(u8, Timings)> = vec![];
+    for run in runs {
+        let day = run.day;
+        let (answers, day_timings) = match &run.outcome {
+            Outcome::Finished(answers, day_timings) => (answers, day_timings),
+            Outcome::Panicked(message) => {
+                eprintln!("Day {day}: panicked: {message}");
+                continue;
+            }
+            Outcome::TimedOut(timeout) => {
+                eprintln!("Day {day}: stopped, still running after {timeout:.1?}");
+                continue;
+            }
+            Outcome::OverBudget(memory) => {
+                let memory = budget::format_bytes(*memory);
+                eprintln!("Day {day}: stopped, needed more than its {memory} memory budget");
+                continue;
+            }
+        };
+        timings.push((day, day_timings.clone()));
+        for (part, answer) in parts.iter().zip(answers) {
+            // Screens are drawn on their own lines
+            match answer {
+                Ok(answer @ Answer::Grid(_)) => println!("Day {day}.{part}:\n{answer}"),
+                Ok(answer) => println!("Day {day}.{part}: {answer}"),
+                Err(e) => eprintln!("Day {day}.{part}: {}", e.diagnostic()),
+            }
+        }
+    }
+
+    if options.time {
+        println!("\n{}", solution::render_timings(&timings, parts));
+    }
+    if runs.len() > 1 {
+        println!("\n{}", render_report(runs, elapsed, threads));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::error::AocError;
+    use crate::solution::Puzzle;
+
+    // Solves without reading its file
+    struct Counts;
+
+    impl Puzzle for Counts {
+        type Input = Vec<u32>;
+
+        fn parse(_: &str) -> Result<Vec<u32>> {
+            Ok(vec![1, 2, 3])
+        }
+
+        fn part1(input: &Vec<u32>) -> Result<Answer> {
+            Ok(Answer::Integer(input.len() as i64))
+        }
+
+        fn part2(_: &Vec<u32>) -> Result<Answer> {
+            Err(AocError::solve("No solution"))
+        }
+    }
+
+    // Panics like an unwrap on an empty input
+    struct Empty;
+
+    impl Puzzle for Empty {
+        type Input = Vec<u32>;
+
+        fn parse(_: &str) -> Result<Vec<u32>> {
+            Ok(vec![])
+        }
+
+        fn part1(input: &Vec<u32>) -> Result<Answer> {
+            Ok(Answer::Integer(*input.first().unwrap() as i64))
+        }
+
+        fn part2(input: &Vec<u32>) -> Result<Answer> {
+            Self::part1(input)
+        }
+    }
+
+    #[test]
+    fn test_run_parallel() {
+        let solutions: Vec<Solution> = test_solutions()
+            .into_iter()
+            .filter(|s| s.day <= 8)
+            .collect();
+        let runs = run_parallel(&test_worker(), &solutions, &[1], 3, &Limits::default());
+        let days: Vec<u8> = runs.iter().map(|run| run.day).collect();
+        assert_eq!(days, (1..=8).collect::<Vec<_>>());
+        for run in &runs {
+            match (&run.outcome, run.day) {
+                (Outcome::Panicked(message), 6) => assert!(message.contains("None")),
+                (Outcome::Finished(answers, _), day) if day != 6 => {
+                    assert_eq!(answers, &vec![Ok(Answer::Integer(3))])
+                }
+                (outcome, day) => panic!("Unexpected outcome of day {day}: {outcome:?}"),
+            }
+        }
+    }
+
+    // Keeps growing until it is stopped, like a simulation with too many rounds
+    struct Runaway;
+
+    impl Puzzle for Runaway {
+        type Input = ();
+
+        fn parse(_: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn part1(_: &()) -> Result<Answer> {
+            let mut states = vec![];
+            loop {
+                states.push(std::hint::black_box(vec![0u8; 1024]));
+                if states.len() % 1024 == 0 {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+
+        fn part2(_: &()) -> Result<Answer> {
+            Self::part1(&())
+        }
+    }
+
+    // Days 1 to 8 but the panicking 6 count, 11 runs away and 12 counts again
+    fn test_solutions() -> Vec<Solution> {
+        (1..=12)
+            .filter_map(|day| match day {
+                6 => Some(Solution::new::<Empty>(day)),
+                11 => Some(Solution::new::<Runaway>(day)),
+                1..=8 | 12 => Some(Solution::new::<Counts>(day)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Days run in this test binary, which only runs the worker test below
+    fn test_worker() -> Worker {
+        let program = env::current_exe().unwrap();
+        Worker::new(
+            program,
+            &["--exact", "runner::tests::worker", "--nocapture", "--quiet"],
+        )
+    }
+
+    // Does nothing unless started by test_worker
+    #[test]
+    fn worker() {
+        serve_worker(&test_solutions());
+    }
+
+    #[test]
+    fn test_limits() {
+        let solutions: Vec<Solution> = test_solutions().into_iter().filter(|s| s.day > 8).collect();
+        let limits = Limits {
+            timeout: None,
+            memory: Some(16 << 20),
+        };
+        let runs = run_parallel(&test_worker(), &solutions, &[1], 2, &limits);
+        assert!(matches!(runs[0].outcome, Outcome::OverBudget(memory) if memory == 16 << 20));
+        assert!(runs[1].succeeded());
+        assert!(runs[1].peak_memory.is_some_and(|memory| memory <= 16 << 20));
+
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(100)),
+            memory: None,
+        };
+        let runs = run_parallel(&test_worker(), &solutions[..1], &[2], 1, &limits);
+        assert!(matches!(runs[0].outcome, Outcome::TimedOut(_)));
+        assert!(runs[0].elapsed < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_render_report() {
+        let run = |day, millis, outcome| DayRun {
+            day,
+            elapsed: Duration::from_millis(millis),
+            peak_memory: Some(1536),
+            outcome,
+        };
+        let timings = Timings::default();
+        let runs = [
+            run(
+                1,
+                2,
+                Outcome::Finished(vec![Ok(Answer::Integer(3))], timings.clone()),
+            ),
+            run(
+                2,
+                1,
+                Outcome::Finished(vec![Err(AocError::solve("No solution"))], timings),
+            ),
+            run(
+                6,
+                0,
+                Outcome::Panicked("called `Option::unwrap()` on a `None` value".to_string()),
+            ),
+            run(9, 1000, Outcome::TimedOut(Duration::from_secs(1))),
+            run(11, 5, Outcome::OverBudget(16 << 20)),
+        ];
+        assert_snapshot!(render_report(&runs, Duration::from_millis(3), 4));
+    }
+}
@@ -0,0 +1,249 @@
+//! Files of a new day, written by `aoc new <day>`
+//! The module starts as a Puzzle that reads lines and solves nothing, its tests fail until it is done
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AocError, Result};
+
+const MODULE: &str = r#"use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parser::read_aoc_file;
+use crate::solution::Puzzle;
+
+pub struct Day{day};
+
+impl Puzzle for Day{day} {
+    // The lines of the input, until they are parsed into something better
+    type Input = Vec<String>;
+
+    fn parse(filename: &str) -> Result<Vec<String>> {
+        Ok(read_aoc_file(filename)?.lines().map(String::from).collect())
+    }
+
+    fn part1(_lines: &Vec<String>) -> Result<Answer> {
+        Err(AocError::solve("Part 1 is not solved yet"))
+    }
+
+    fn part2(_lines: &Vec<String>) -> Result<Answer> {
+        Err(AocError::solve("Part 2 is not solved yet"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let lines = Day{day}::parse("src/examples/day{day}.txt").unwrap();
+
+        assert!(
+            !lines.is_empty(),
+            "src/examples/day{day}.txt is empty, paste the example of the puzzle statement"
+        );
+    }
+}
+"#;
+
+const EXAMPLES: &str = r#"# Examples of the puzzle statement, each one is a generated test (see src/examples.rs)
+# Replace the answers with the ones of the puzzle statement
+
+[[example]]
+file = "day{day}.txt"
+part1 = 0
+part2 = 0
+"#;
+
+const REGISTRY: &str = "src/exercises/mod.rs";
+
+fn from_template(template: &str, day: u8) -> String {
+    template.replace("{day}", &day.to_string())
+}
+
+fn file_error(file: &Path, message: &str) -> AocError {
+    AocError::Io {
+        file: file.display().to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Adds the module and its solution to the source of exercises/mod.rs
+/// Modules are kept in the order of their names, and solutions in the order of days
+pub fn register(source: &str, day: u8) -> Result<String> {
+    let module = format!("pub mod day{day};");
+    let solution = format!("Solution::new::<day{day}::Day{day}>({day}),");
+    if source.lines().any(|line| line.trim() == module) {
+        let message = format!("Day {day} is already registered");
+        return Err(file_error(Path::new(REGISTRY), &message));
+    }
+
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+    let modules: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].starts_with("pub mod day"))
+        .collect();
+    let Some(&last_module) = modules.last() else {
+        return Err(file_error(Path::new(REGISTRY), "No day module found"));
+    };
+    // As sorted by rustfmt, day10 comes before day2
+    let name = |line: &str| {
+        line.trim_start_matches("pub mod ")
+            .trim_end_matches(';')
+            .to_string()
+    };
+    let position = modules
+        .iter()
+        .copied()
+        .find(|&i| name(&lines[i]) > name(&module))
+        .unwrap_or(last_module + 1);
+    lines.insert(position, module);
+
+    let registered_day = |line: &str| {
+        let day = line
+            .trim()
+            .strip_prefix("Solution::new::<day")?
+            .split_once(':')?
+            .0;
+        day.parse::<u8>().ok()
+    };
+    let solutions: Vec<usize> = (0..lines.len())
+        .filter(|&i| registered_day(&lines[i]).is_some())
+        .collect();
+    let Some(&last_solution) = solutions.last() else {
+        return Err(file_error(
+            Path::new(REGISTRY),
+            "No registered solution found",
+        ));
+    };
+    let indent: String = lines[last_solution]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+    let position = solutions
+        .iter()
+        .copied()
+        .find(|&i| registered_day(&lines[i]).is_some_and(|d| d > day))
+        .unwrap_or(last_solution + 1);
+    lines.insert(position, format!("{indent}{solution}"));
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Writes the files of a new day in the crate at root, returns the created and changed files
+/// Nothing is written if the day already has a module
+pub fn create(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let exercises = root.join("src/exercises");
+    let registry = root.join(REGISTRY);
+    let source = fs::read_to_string(&registry).map_err(|e| AocError::io(&registry, e))?;
+    let registered = register(&source, day)?;
+
+    let files = [
+        (
+            exercises.join(format!("day{day}.rs")),
+            from_template(MODULE, day),
+        ),
+        (root.join(format!("src/files/day{day}")), String::new()),
+        (
+            root.join(format!("src/examples/day{day}.txt")),
+            String::new(),
+        ),
+        (
+            root.join(format!("src/examples/day{day}.toml")),
+            from_template(EXAMPLES, day),
+        ),
+    ];
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        return Err(file_error(path, "Already exists, not overwritten"));
+    }
+    let mut written = vec![];
+    for (path, content) in files {
+        fs::write(&path, content).map_err(|e| AocError::io(&path, e))?;
+        written.push(path);
+    }
+    fs::write(&registry, registered).map_err(|e| AocError::io(&registry, e))?;
+    written.push(registry);
+    Ok(written)
+}
+
+/// Writes the files of a new day in this crate and tells what to fill in next
+/// Returns whether every file was written
+pub fn new_day(day: u8) -> bool {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match create(root, day) {
+        Ok(written) => {
+            for path in written {
+                let path = path.strip_prefix(root).unwrap_or(&path);
+                println!("Day {day}: wrote {}", path.display());
+            }
+            println!(
+                "Paste the example in src/examples/day{day}.txt with its answers in \
+                 src/examples/day{day}.toml, then fetch the input with `fetch {day}`"
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("Day {day}: error: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::testing::TempDir;
+
+    const SOURCE: &str = "use crate::solution::Solution;
+
+pub mod day1;
+pub mod day13;
+pub mod day2;
+
+/// Every solved day, in order
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new::<day1::Day1>(1),
+        Solution::new::<day2::Day2>(2),
+        Solution::new::<day13::Day13>(13),
+    ]
+}
+";
+
+    #[test]
+    fn test_register() {
+        assert_snapshot!(register(SOURCE, 14).unwrap());
+        let registered = register(SOURCE, 10).unwrap();
+        assert!(registered.contains("pub mod day1;\npub mod day10;\npub mod day13;"));
+        assert!(registered.contains(
+            "Solution::new::<day2::Day2>(2),\n        Solution::new::<day10::Day10>(10),\n"
+        ));
+        assert!(register(SOURCE, 13).is_err());
+    }
+
+    #[test]
+    fn test_create() {
+        let dir = TempDir::new("new");
+        let root = dir.path();
+        for dir in ["src/exercises", "src/files", "src/examples"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join(REGISTRY), SOURCE).unwrap();
+
+        let written = create(root, 14).unwrap();
+        assert_eq!(written.len(), 5);
+        let module = fs::read_to_string(root.join("src/exercises/day14.rs")).unwrap();
+        assert!(module.contains("impl Puzzle for Day14 {"));
+        assert!(module.contains("src/examples/day14.txt is empty"));
+        assert_eq!(
+            fs::read_to_string(root.join("src/files/day14")).unwrap(),
+            ""
+        );
+
+        // A day is only created once
+        assert!(create(root, 14).is_err());
+        fs::write(root.join(REGISTRY), SOURCE).unwrap();
+        assert!(create(root, 14).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::input;
+use crate::table::{self, Align};
+
+/// Named values changing how a puzzle is solved, such as the rope length of day 9
+/// Examples use them when they are smaller than the real puzzle
+pub type Params = BTreeMap<String, i64>;
+
+/// A day's puzzle
+/// The input is parsed once, then shared by both parts
+pub trait Puzzle {
+    type Input;
+
+    fn parse(filename: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+
+    /// Solves a part with parameters, puzzles that have some override this to read them
+    fn solve(input: &Self::Input, part: u8, params: &Params) -> Result<Answer> {
+        if let Some(name) = params.keys().next() {
+            return Err(AocError::solve(format!("Unknown parameter {name}")));
+        }
+        match part {
+            1 => Self::part1(input),
+            2 => Self::part2(input),
+            _ => Err(AocError::solve(format!("Puzzles have no part {part}"))),
+        }
+    }
+}
+
+/// A solved day, as registered in exercises::solutions
+/// The puzzle type is erased so that every day fits in the same list, its input is kept as Any
+#[derive(Clone)]
+pub struct Solution {
+    pub day: u8,
+    pub input: String,
+    parser: fn(&str) -> Result<Box<dyn Any>>,
+    solver: fn(&dyn Any, u8, &Params) -> Result<Answer>,
+}
+
+/// An input parsed by a solution, ready to solve its parts
+pub struct Parsed<'a> {
+    solution: &'a Solution,
+    input: Box<dyn Any>,
+}
+
+/// How long each phase of a run took
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timings {
+    pub parse: Duration,
+    /// In the same order as the requested parts, empty when parsing failed
+    pub parts: Vec<Duration>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.parts.iter().sum::<Duration>()
+    }
+}
+
+impl Solution {
+    pub fn new<P: Puzzle + 'static>(day: u8) -> Solution
+    where
+        P::Input: 'static,
+    {
+        Solution {
+            day,
+            input: input::input_name(day),
+            parser: |filename| Ok(Box::new(P::parse(filename)?)),
+            solver: |input, part, params| {
+                let input = input
+                    .downcast_ref::<P::Input>()
+                    .expect("inputs are parsed by the same puzzle");
+                P::solve(input, part, params)
+            },
+        }
+    }
+
+    /// Errors are located in the parsed file
+    pub fn parse(&self, filename: &str) -> Result<Parsed<'_>> {
+        let input = (self.parser)(filename).map_err(|e| e.in_file(filename))?;
+        Ok(Parsed {
+            solution: self,
+            input,
+        })
+    }
+
+    /// Parts are numbered from 1, as on the website
+    /// Answers are returned in the same order as the requested parts
+    /// A parse error is reported for every part, as none of them can run
+    pub fn run(&self, parts: &[u8]) -> Vec<Result<Answer>> {
+        self.run_timed(parts).0
+    }
+
+    /// Same as run, with the time taken by parsing and by each part
+    pub fn run_timed(&self, parts: &[u8]) -> (Vec<Result<Answer>>, Timings) {
+        self.run_on(&self.input, parts, &Params::new())
+    }
+
+    /// Same as run_timed, on another input such as an example
+    pub fn run_on(
+        &self,
+        filename: &str,
+        parts: &[u8],
+        params: &Params,
+    ) -> (Vec<Result<Answer>>, Timings) {
+        let start = Instant::now();
+        let parsed = self.parse(filename);
+        let mut timings = Timings {
+            parse: start.elapsed(),
+            parts: vec![],
+        };
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => return (parts.iter().map(|_| Err(e.clone())).collect(), timings),
+        };
+        let answers = parts
+            .iter()
+            .map(|part| {
+                let start = Instant::now();
+                let answer = parsed.solve(*part, params);
+                timings.parts.push(start.elapsed());
+                answer
+            })
+            .collect();
+        (answers, timings)
+    }
+}
+
+impl Parsed<'_> {
+    pub fn solve(&self, part: u8, params: &Params) -> Result<Answer> {
+        (self.solution.solver)(self.input.as_ref(), part, params)
+    }
+}
+
+/// One row per day with the time of each phase, then the sum of every day
+/// Parts that were not run are shown as -
+pub fn render_timings(days: &[(u8, Timings)], parts: &[u8]) -> String {
+    let part_time = |timings: &Timings, part: u8| {
+        let index = parts.iter().position(|&p| p == part)?;
+        timings.parts.get(index).copied()
+    };
+    let format =
+        |duration: Option<Duration>| duration.map_or("-".to_string(), |d| format!("{d:.1?}"));
+
+    let mut rows = vec![];
+    for (day, timings) in days {
+        rows.push(vec![
+            day.to_string(),
+            format(Some(timings.parse)),
+            format(part_time(timings, 1)),
+            format(part_time(timings, 2)),
+            format(Some(timings.total())),
+        ]);
+    }
+    let sum = |phase: &dyn Fn(&Timings) -> Option<Duration>| {
+        let durations: Vec<_> = days.iter().filter_map(|(_, t)| phase(t)).collect();
+        (!durations.is_empty()).then(|| durations.into_iter().sum())
+    };
+    rows.push(vec![
+        "All".to_string(),
+        format(sum(&|t| Some(t.parse))),
+        format(sum(&|t| part_time(t, 1))),
+        format(sum(&|t| part_time(t, 2))),
+        format(sum(&|t| Some(t.total()))),
+    ]);
+
+    let columns = ["Day", "Parse", "Part 1", "Part 2", "Total"].map(|name| (name, Align::Right));
+    table::render(&columns, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_render_timings() {
+        let micros = Duration::from_micros;
+        let days = [
+            (
+                1,
+                Timings {
+                    parse: micros(120),
+                    parts: vec![micros(3), micros(15)],
+                },
+            ),
+            (
+                12,
+                Timings {
+                    parse: micros(450),
+                    parts: vec![micros(2_500), micros(180_300)],
+                },
+            ),
+            (
+                13,
+                Timings {
+                    parse: micros(900),
+                    parts: vec![],
+                },
+            ),
+        ];
+        assert_snapshot!(render_timings(&days, &[1, 2]));
+
+        let part2 = Timings {
+            parse: micros(120),
+            parts: vec![micros(15)],
+        };
+        assert_snapshot!("render_timings_part2", render_timings(&[(1, part2)], &[2]));
+    }
+}
@@ -0,0 +1,297 @@
+//! Answers sent to the website, and what it said about them
+//! The history is kept to never send an answer that is already known to be wrong
+
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Answer;
+use crate::client::{Client, Config};
+use crate::error::Result;
+use crate::history;
+use crate::solution::Solution;
+
+/// What the website said about a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Verdict::Right => "right",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// The page answered to a submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Judged(Verdict),
+    /// Too soon after the previous submission, the answer was not looked at
+    RateLimited(Duration),
+    /// The part was already solved, the answer was not looked at
+    AlreadySolved,
+}
+
+fn wait_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").expect("valid wait pattern")
+    })
+}
+
+/// Reads the outcome from the text of the answer page
+pub fn parse_outcome(page: &str) -> Option<Outcome> {
+    if page.contains("That's the right answer") {
+        return Some(Outcome::Judged(Verdict::Right));
+    }
+    if page.contains("That's not the right answer") {
+        let verdict = if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        };
+        return Some(Outcome::Judged(verdict));
+    }
+    if page.contains("You gave an answer too recently") {
+        let wait = wait_pattern().captures(page).map_or(0, |captures| {
+            let minutes: u64 = captures
+                .get(1)
+                .map_or(0, |m| m.as_str().parse().unwrap_or(0));
+            let seconds: u64 = captures[2].parse().unwrap_or(0);
+            minutes * 60 + seconds
+        });
+        return Some(Outcome::RateLimited(Duration::from_secs(wait)));
+    }
+    if page.contains("You don't seem to be solving the right level") {
+        return Some(Outcome::AlreadySolved);
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+/// Every judged submission, stored as JSON
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    pub fn load(path: &Path) -> Result<History> {
+        Ok(History {
+            path: path.to_path_buf(),
+            submissions: history::load(path, "history")?,
+        })
+    }
+
+    /// Why an answer should not be sent, if what was already submitted tells it can't be right
+    pub fn refusal(&self, day: u8, part: u8, answer: &Answer) -> Option<String> {
+        if let Answer::Grid(_) = answer {
+            return Some("screens have to be read and submitted by hand".to_string());
+        }
+        let previous = self
+            .submissions
+            .iter()
+            .filter(|s| s.day == day && s.part == part);
+        for submission in previous {
+            let known = &submission.answer;
+            let reason = match (submission.verdict, answer, known) {
+                (Verdict::Right, _, _) if known == answer => {
+                    format!("{answer} was already accepted")
+                }
+                (Verdict::Right, _, _) => format!("the part was already solved with {known}"),
+                (_, _, _) if known == answer => {
+                    format!("{answer} was already rejected as {}", submission.verdict)
+                }
+                (Verdict::TooHigh, Answer::Integer(n), Answer::Integer(high)) if n >= high => {
+                    format!("{answer} can't be right, {high} is already too high")
+                }
+                (Verdict::TooLow, Answer::Integer(n), Answer::Integer(low)) if n <= low => {
+                    format!("{answer} can't be right, {low} is already too low")
+                }
+                _ => continue,
+            };
+            return Some(reason);
+        }
+        None
+    }
+
+    pub fn record(&mut self, submission: Submission) -> Result<()> {
+        self.submissions.push(submission);
+        history::save(&self.path, &self.submissions)
+    }
+}
+
+/// Solves a part and sends its answer with send_answer, returns whether it was accepted
+pub fn submit(solution: &Solution, part: u8) -> bool {
+    let day = solution.day;
+    let answer = match solution.run(&[part]).remove(0) {
+        Ok(answer) => answer,
+        Err(e) => {
+            eprintln!("Day {day}.{part}: {}", e.diagnostic());
+            return false;
+        }
+    };
+    send_answer(day, part, answer).unwrap_or_else(|e| {
+        eprintln!("Day {day}.{part}: error: {e}");
+        false
+    })
+}
+
+/// Sends an answer and records the verdict, unless the history already rules it out
+/// Returns whether the part is now solved on the website
+pub fn send_answer(day: u8, part: u8, answer: Answer) -> Result<bool> {
+    let config = Config::from_env()?;
+    let mut history = History::load(&config.cache_dir.join("submissions.json"))?;
+    if let Some(reason) = history.refusal(day, part, &answer) {
+        println!("Day {day}.{part}: not sent, {reason}");
+        return Ok(false);
+    }
+
+    match Client::new(config).submit_answer(day, part, &answer)? {
+        Outcome::Judged(verdict) => {
+            println!("Day {day}.{part}: {answer} is {verdict}");
+            history.record(Submission {
+                day,
+                part,
+                answer,
+                verdict,
+            })?;
+            Ok(verdict == Verdict::Right)
+        }
+        Outcome::RateLimited(wait) => {
+            println!(
+                "Day {day}.{part}: rate limited, retry in {}s",
+                wait.as_secs()
+            );
+            Ok(false)
+        }
+        Outcome::AlreadySolved => {
+            println!("Day {day}.{part}: already solved on the website");
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_parse_outcome() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            parse_outcome(&page(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Some(Outcome::Judged(Verdict::Right))
+        );
+        assert_eq!(
+            parse_outcome(&page(
+                "That's not the right answer; your answer is too high. Please wait one minute."
+            )),
+            Some(Outcome::Judged(Verdict::TooHigh))
+        );
+        assert_eq!(
+            parse_outcome(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Some(Outcome::Judged(Verdict::TooLow))
+        );
+        assert_eq!(
+            parse_outcome(&page("That's not the right answer. If you're stuck...")),
+            Some(Outcome::Judged(Verdict::Wrong))
+        );
+        assert_eq!(
+            parse_outcome(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again. You have 1m 5s left to wait."
+            )),
+            Some(Outcome::RateLimited(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            parse_outcome(&page(
+                "You don't seem to be solving the right level. Did you already complete it?"
+            )),
+            Some(Outcome::AlreadySolved)
+        );
+        assert_eq!(parse_outcome(&page("Something else")), None);
+    }
+
+    #[test]
+    fn test_history_refusal() {
+        let dir = TempDir::new("history");
+        let path = dir.join("submissions.json");
+        let mut history = History::load(&path).unwrap();
+        let submission = |part, answer: Answer, verdict| Submission {
+            day: 7,
+            part,
+            answer,
+            verdict,
+        };
+        history
+            .record(submission(1, 100.into(), Verdict::TooHigh))
+            .unwrap();
+        history
+            .record(submission(1, 20.into(), Verdict::TooLow))
+            .unwrap();
+        history
+            .record(submission(1, 50.into(), Verdict::Wrong))
+            .unwrap();
+        history
+            .record(submission(2, "ABC".into(), Verdict::Right))
+            .unwrap();
+
+        // The history is read back from its file
+        let history = History::load(&path).unwrap();
+        let refusal = |part, answer: Answer| history.refusal(7, part, &answer);
+        assert_eq!(refusal(1, 30.into()), None);
+        assert_eq!(
+            refusal(1, 120.into()),
+            Some("120 can't be right, 100 is already too high".to_string())
+        );
+        assert_eq!(
+            refusal(1, 20.into()),
+            Some("20 was already rejected as too low".to_string())
+        );
+        assert_eq!(
+            refusal(1, 50.into()),
+            Some("50 was already rejected as wrong".to_string())
+        );
+        assert_eq!(
+            refusal(2, "ABC".into()),
+            Some("ABC was already accepted".to_string())
+        );
+        assert_eq!(
+            refusal(2, "XYZ".into()),
+            Some("the part was already solved with ABC".to_string())
+        );
+        assert!(refusal(2, vec![vec![true]].into()).is_some());
+        assert_eq!(history.refusal(8, 1, &120.into()), None);
+    }
+}
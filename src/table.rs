@@ -0,0 +1,70 @@
+//! Plain text tables, as printed by run, verify and bench
+
+use std::iter;
+
+/// Side of its column a cell is aligned on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A header line, then one line per row, every column being as wide as its widest cell
+/// Columns are separated by two spaces, and lines end without trailing spaces
+pub fn render(columns: &[(&str, Align)], rows: &[Vec<String>]) -> String {
+    let header: Vec<String> = columns.iter().map(|(name, _)| name.to_string()).collect();
+    let lines: Vec<&Vec<String>> = iter::once(&header).chain(rows).collect();
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|i| {
+            lines
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    lines
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(columns)
+                .zip(&widths)
+                .map(|((cell, (_, align)), &width)| match align {
+                    Align::Left => format!("{cell:<width$}"),
+                    Align::Right => format!("{cell:>width$}"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let columns = [
+            ("Day", Align::Right),
+            ("Status", Align::Left),
+            ("", Align::Left),
+        ];
+        let rows = [
+            vec!["7".to_string(), "ok".to_string(), String::new()],
+            vec![
+                "12".to_string(),
+                "TIMEOUT".to_string(),
+                "after 1.0s".to_string(),
+            ],
+        ];
+        assert_eq!(
+            render(&columns, &rows),
+            "Day  Status\n  7  ok\n 12  TIMEOUT  after 1.0s"
+        );
+    }
+}
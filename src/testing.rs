@@ -0,0 +1,34 @@
+// Helpers shared by the tests of several modules
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+
+// An empty directory of its own for a test, deleted with its files when dropped
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        // Tests run in parallel, possibly several of them with the same name
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+        let count = CREATED.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("aoc-2022-{name}-{}-{count}", process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("the temporary directory is writable");
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
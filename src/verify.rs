@@ -0,0 +1,246 @@
+//! Checks the answers of the real inputs against the accepted ones of answers.toml
+//! The manifest sits with the inputs, so it is found like them (see input::read)
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use serde::Deserialize;
+
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::input;
+use crate::parser::parse_toml;
+use crate::solution::Solution;
+use crate::table::{self, Align};
+
+pub const MANIFEST: &str = "answers.toml";
+
+/// An accepted answer, integers are compared as numbers and the rest as printed
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Expected {
+    Integer(i64),
+    Text(String),
+}
+
+impl Expected {
+    pub fn matches(&self, answer: &Answer) -> bool {
+        match (self, answer) {
+            (Expected::Integer(expected), Answer::Integer(n)) => expected == n,
+            (Expected::Text(expected), answer) => expected.trim() == answer.to_string().trim(),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Integer(n) => write!(f, "{n}"),
+            Expected::Text(text) => write!(f, "{}", text.trim()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    part1: Option<Expected>,
+    part2: Option<Expected>,
+}
+
+/// Accepted answers by day
+#[derive(Debug, Default)]
+pub struct Manifest {
+    days: BTreeMap<String, DayAnswers>,
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Manifest> {
+        let days = parse_toml(text, MANIFEST)?;
+        Ok(Manifest { days })
+    }
+
+    pub fn load() -> Result<Manifest> {
+        Manifest::parse(&input::read(MANIFEST)?)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Expected> {
+        let answers = self.days.get(&input::input_name(day))?;
+        match part {
+            1 => answers.part1.as_ref(),
+            2 => answers.part2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// The answer differs from the accepted one
+    Fail(Expected),
+    /// No accepted answer is recorded
+    Missing,
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub status: Status,
+}
+
+impl Check {
+    pub fn new(day: u8, part: u8, answer: Result<Answer>, expected: Option<&Expected>) -> Check {
+        let (answer, status) = match (answer, expected) {
+            (Err(e), _) => (None, Status::Error(e.to_string())),
+            (Ok(answer), None) => (Some(answer), Status::Missing),
+            (Ok(answer), Some(expected)) if expected.matches(&answer) => {
+                (Some(answer), Status::Pass)
+            }
+            (Ok(answer), Some(expected)) => (Some(answer), Status::Fail(expected.clone())),
+        };
+        Check {
+            day,
+            part,
+            answer,
+            status,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Fail(_) | Status::Error(_))
+    }
+}
+
+/// Runs both parts of every solution and compares them with the manifest
+pub fn verify(solutions: &[Solution], manifest: &Manifest) -> Vec<Check> {
+    solutions
+        .iter()
+        .flat_map(|solution| {
+            let day = solution.day;
+            (1..=2)
+                .zip(solution.run(&[1, 2]))
+                .map(move |(part, answer)| Check::new(day, part, answer, manifest.get(day, part)))
+        })
+        .collect()
+}
+
+// Screens are summarized, they are too tall for a table
+fn short(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(rows) => format!("screen {}x{}", rows.first().map_or(0, Vec::len), rows.len()),
+        answer => answer.to_string(),
+    }
+}
+
+pub fn render_table(checks: &[Check]) -> String {
+    let rows: Vec<Vec<String>> = checks
+        .iter()
+        .map(|check| {
+            let answer = check.answer.as_ref().map(short).unwrap_or_default();
+            let (status, detail) = match &check.status {
+                Status::Pass => ("pass", answer),
+                Status::Fail(Expected::Integer(n)) => ("FAIL", format!("{answer}, expected {n}")),
+                Status::Fail(Expected::Text(text)) => {
+                    let expected = short(&Answer::Text(text.trim().to_string()));
+                    ("FAIL", format!("{answer}, expected {expected}"))
+                }
+                Status::Missing => ("missing", format!("{answer}, not in {MANIFEST}")),
+                Status::Error(message) => ("ERROR", message.lines().next().unwrap_or("").into()),
+            };
+            vec![
+                check.day.to_string(),
+                check.part.to_string(),
+                status.to_string(),
+                detail,
+            ]
+        })
+        .collect();
+    let columns = [
+        ("Day", Align::Right),
+        ("Part", Align::Right),
+        ("Status", Align::Left),
+        ("Answer", Align::Left),
+    ];
+
+    let mut table = vec![table::render(&columns, &rows)];
+    let failed = checks.iter().filter(|c| c.failed()).count();
+    table.push(format!(
+        "{} passed, {failed} failed, {} missing",
+        checks.iter().filter(|c| c.status == Status::Pass).count(),
+        checks
+            .iter()
+            .filter(|c| c.status == Status::Missing)
+            .count(),
+    ));
+    table.join("\n")
+}
+
+/// Checks the answers of the days against the manifest and prints the table of render_table
+/// Returns whether none of them failed, days missing from the manifest are not failures
+pub fn check_answers(solutions: &[Solution]) -> bool {
+    let manifest = match Manifest::load() {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("{}", e.diagnostic());
+            return false;
+        }
+    };
+    let checks = verify(solutions, &manifest);
+    println!("{}", render_table(&checks));
+    !checks.iter().any(Check::failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+    use crate::error::AocError;
+
+    #[test]
+    fn test_manifest() {
+        let manifest = Manifest::parse(
+            "[day5]\npart1 = \"CMZ\"\n\n[day10]\npart1 = 13140\npart2 = \"\"\"\n#.\n.#\"\"\"\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.get(5, 1), Some(&Expected::Text("CMZ".to_string())));
+        assert_eq!(manifest.get(5, 2), None);
+        assert_eq!(manifest.get(10, 1), Some(&Expected::Integer(13140)));
+        let screen = Answer::Grid(vec![vec![true, false], vec![false, true]]);
+        assert!(manifest.get(10, 2).unwrap().matches(&screen));
+        assert!(!Expected::Integer(12).matches(&Answer::Text("12".to_string())));
+
+        let error = Manifest::parse("[day1]\npart1 = 1\npart3 = 2\n").unwrap_err();
+        assert!(matches!(
+            error,
+            AocError::Parse { location, .. } if location.line == Some(3)
+        ));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let checks = [
+            Check::new(
+                1,
+                1,
+                Ok(Answer::Integer(24000)),
+                Some(&Expected::Integer(24000)),
+            ),
+            Check::new(
+                1,
+                2,
+                Ok(Answer::Integer(45001)),
+                Some(&Expected::Integer(45000)),
+            ),
+            Check::new(5, 1, Ok("CMZ".into()), Some(&Expected::Text("MCD".into()))),
+            Check::new(10, 2, Ok(Answer::Grid(vec![vec![true; 40]; 6])), None),
+            Check::new(13, 1, Err(AocError::solve("Unable to find path")), None),
+        ];
+        assert_snapshot!(render_table(&checks));
+    }
+}
@@ -0,0 +1,185 @@
+//! Files a day depends on, polled for changes by `aoc watch <day>`
+//! Polling the modification times is enough for a handful of files, and works everywhere
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use std::{env, fs};
+
+use crate::input;
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification time of each watched file, None for a missing one
+pub type Snapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+/// The module of the day, the input it reads, and its examples with their manifest
+pub fn watched_files(day: u8) -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let name = input::input_name(day);
+    // An embedded input is rebuilt from src/files when it changes
+    let input = match input::find(&name) {
+        input::Source::File(path) => path,
+        input::Source::Embedded(_) => input::locate(&name),
+    };
+    let mut files = vec![root.join(format!("src/exercises/day{day}.rs")), input];
+    // Such as day9.toml, day9_1.txt and day9_2.txt, but not day10.txt
+    let prefixes = [format!("day{day}."), format!("day{day}_")];
+    if let Ok(entries) = fs::read_dir(root.join("src/examples")) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if prefixes.iter().any(|prefix| name.starts_with(prefix)) {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+pub fn snapshot(files: &[PathBuf]) -> Snapshot {
+    files
+        .iter()
+        .map(|file| {
+            let modified = fs::metadata(file).and_then(|m| m.modified()).ok();
+            (file.clone(), modified)
+        })
+        .collect()
+}
+
+/// Files added, removed or modified between two snapshots
+pub fn changes(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = after
+        .iter()
+        .filter(|(file, modified)| before.get(*file) != Some(modified))
+        .map(|(file, _)| file.clone())
+        .collect();
+    changed.extend(
+        before
+            .keys()
+            .filter(|file| !after.contains_key(*file))
+            .cloned(),
+    );
+    changed
+}
+
+/// Blocks until a file of the day changes, and returns the changed files
+/// Editors often write a file in several steps, so it waits for them to settle
+pub fn wait_for_change(day: u8, previous: &mut Snapshot) -> Vec<PathBuf> {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(&watched_files(day));
+        let mut changed = changes(previous, &current);
+        if changed.is_empty() {
+            continue;
+        }
+        *previous = current;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = snapshot(&watched_files(day));
+            let settling = changes(previous, &current);
+            if settling.is_empty() {
+                break;
+            }
+            changed.extend(settling);
+            *previous = current;
+        }
+        changed.sort();
+        changed.dedup();
+        return changed;
+    }
+}
+
+/// Runs cargo in this crate with the profile this code was built with, returns whether it succeeded
+/// The first argument is the subcommand, quieted, the others follow it
+pub fn cargo(args: &[&str]) -> bool {
+    let mut command = Command::new(env::var("CARGO").unwrap_or("cargo".to_string()));
+    command.current_dir(env!("CARGO_MANIFEST_DIR"));
+    match args.split_first() {
+        Some((subcommand, rest)) => {
+            command.arg(subcommand).arg("--quiet");
+            if !cfg!(debug_assertions) {
+                command.arg("--release");
+            }
+            command.args(rest);
+        }
+        None => return false,
+    }
+    command.status().is_ok_and(|status| status.success())
+}
+
+/// Rebuilds, then runs the tests, examples and solvers of a day with the new code
+pub fn check_day(day: u8) {
+    if !cargo(&["build"]) {
+        return;
+    }
+    let tests = [
+        format!("exercises::day{day}::"),
+        format!("examples::generated::day{day}_"),
+    ];
+    cargo(&["test", "--lib", "--", &tests[0], &tests[1]]);
+    let day = day.to_string();
+    cargo(&["run", "--", "examples", &day]);
+    println!();
+    cargo(&["run", "--", &day]);
+}
+
+/// Checks a day with check_day, then again whenever one of its files changes, until interrupted
+pub fn watch(day: u8) -> ! {
+    let files = watched_files(day);
+    let mut previous = snapshot(&files);
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let name = |path: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+    let names: Vec<String> = files.iter().map(|file| name(file)).collect();
+    println!("Watching {}, Ctrl-C to stop", names.join(", "));
+    check_day(day);
+    loop {
+        let changed = wait_for_change(day, &mut previous);
+        let names: Vec<String> = changed.iter().map(|file| name(file)).collect();
+        println!("\n=== {} changed ===", names.join(", "));
+        check_day(day);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_watched_files() {
+        let names: Vec<String> = watched_files(9)
+            .iter()
+            .map(|file| file.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert!(names.contains(&"day9.rs".to_string()));
+        assert!(names.contains(&"day9.toml".to_string()));
+        assert!(names.contains(&"day9_1.txt".to_string()));
+        assert!(!names.iter().any(|name| name.starts_with("day1")));
+    }
+
+    #[test]
+    fn test_changes() {
+        let dir = TempDir::new("watch");
+        let (kept, edited, created) = (dir.join("kept"), dir.join("edited"), dir.join("created"));
+        fs::write(&kept, "1").unwrap();
+        fs::write(&edited, "1").unwrap();
+        let files = [kept.clone(), edited.clone(), created.clone()];
+
+        let before = snapshot(&files);
+        assert_eq!(changes(&before, &snapshot(&files)), Vec::<PathBuf>::new());
+
+        let file = fs::File::options().write(true).open(&edited).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        fs::write(&created, "").unwrap();
+        assert_eq!(changes(&before, &snapshot(&files)), vec![created, edited]);
+    }
+}